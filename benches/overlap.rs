@@ -25,6 +25,16 @@ fn bench_orthonormalize_30(c: &mut Criterion) {
         },
         &[16, 64, 256, 1024],
     );
+
+    #[cfg(feature = "rayon")]
+    c.bench_function_over_inputs(
+        "par_orthonormalize/30",
+        |b, &&n| {
+            let input = generate_basis(n, 30);
+            b.iter(|| input.par_orthonormalize() );
+        },
+        &[16, 64, 256, 1024],
+    );
 }
 
 fn bench_sqnorm(c: &mut Criterion) {
@@ -50,12 +60,38 @@ fn bench_overlap(c: &mut Criterion) {
     );
 }
 
+fn bench_dot_matrix(c: &mut Criterion) {
+    c.bench_function_over_inputs(
+        "dot_matrix/blocked",
+        |b, &&n| {
+            let bras = generate_basis(n, 1024);
+            let kets = generate_basis(n, 1024);
+            b.iter(|| bras.dot_matrix(&kets) );
+        },
+        &[16, 64, 256],
+    );
+    c.bench_function_over_inputs(
+        "dot_matrix/naive",
+        |b, &&n| {
+            let bras = generate_basis(n, 1024);
+            let kets = generate_basis(n, 1024);
+            b.iter(|| {
+                bras.iter()
+                    .flat_map(|bra| kets.iter().map(move |ket| bra.dot(ket)))
+                    .collect::<Vec<_>>()
+            });
+        },
+        &[16, 64, 256],
+    );
+}
+
 criterion_group!{
     name = benches_dot;
     config = Criterion::default();
     targets =
         bench_overlap,
         bench_sqnorm,
+        bench_dot_matrix,
 }
 
 criterion_group!{
@@ -1,6 +1,6 @@
 macro_rules! impl_common_trash {
     (
-        types: [$Basis:ident, $Ket:ident, $KetRef:ident]
+        types: [$Basis:ident, $Ket:ident, $KetRef:ident, $KetMut:ident]
         traits: [$AsKetRef:ident]
         // what the datatype conceptually contains
         elements: [$Complex:ident { $a:ident : $A:path, $b:ident : $B:path }]
@@ -14,6 +14,7 @@ macro_rules! impl_common_trash {
         pub type Iter<'a> = Box<Iterator<Item=$Complex> + 'a>;
 
         /// An owned ket.
+        #[cfg_attr(feature = "serde", derive(Serialize))]
         #[derive(Debug, Clone)]
         pub struct $Ket {
             pub(crate) $a: Vec<$A>,
@@ -34,6 +35,12 @@ macro_rules! impl_common_trash {
                 $KetRef { $a, $b }
             }
 
+            #[inline]
+            pub fn as_mut(&mut self) -> $KetMut {
+                let $Ket { ref mut $a, ref mut $b } = *self;
+                $KetMut { $a, $b }
+            }
+
             #[inline]
             pub fn $a(&self) -> &[$A] { &self.$a }
             #[inline]
@@ -58,6 +65,9 @@ macro_rules! impl_common_trash {
             /// Computes `<self|other>` (i.e. `self` becomes the bra)
             #[inline]
             pub fn dot<K: $AsKetRef>(&self, other: K) -> $Rect { self.as_ref().dot(other) }
+            /// Computes `<self|other>`, failing if the lengths differ.
+            #[inline]
+            pub fn try_dot<K: $AsKetRef>(&self, other: K) -> ::errors::Result<$Rect> { self.as_ref().try_dot(other) }
 
             #[inline]
             pub fn to_normalized(&self) -> $Ket { self.as_ref().to_normalized() }
@@ -70,6 +80,18 @@ macro_rules! impl_common_trash {
             pub fn overlap<K: $AsKetRef>(&self, other: K) -> $Real { self.as_ref().overlap(other) }
             #[inline]
             pub fn iter(&self) -> Iter { self.as_ref().iter() }
+
+            // methods forwarded to KetMut
+
+            /// Computes `self += alpha * x` in place.
+            ///
+            /// Panics if the lengths differ.
+            #[inline]
+            pub fn axpy<K: $AsKetRef>(&mut self, alpha: $Rect, x: K) { self.as_mut().axpy(alpha, x) }
+            #[inline]
+            pub fn try_axpy<K: $AsKetRef>(&mut self, alpha: $Rect, x: K) -> ::errors::Result<()> {
+                self.as_mut().try_axpy(alpha, x)
+            }
         }
 
         impl $Ket {
@@ -131,8 +153,16 @@ macro_rules! impl_common_trash {
             #[inline]
             fn as_ket_ref(&self) -> $KetRef { (**self).as_ket_ref() }
         }
+        /// A tuple of the two component slices.
+        impl<X: AsRef<[$A]>, Y: AsRef<[$B]>> $AsKetRef for (X, Y) {
+            #[inline]
+            fn as_ket_ref(&self) -> $KetRef { $KetRef { $a: self.0.as_ref(), $b: self.1.as_ref() } }
+        }
 
         /// A not-owned ket.
+        ///
+        /// Serializes identically to the owned ket.
+        #[cfg_attr(feature = "serde", derive(Serialize))]
         #[derive(Debug, Copy, Clone)]
         pub struct $KetRef<'a> {
             pub(crate) $a: &'a [$A],
@@ -196,6 +226,74 @@ macro_rules! impl_common_trash {
             #[inline]
             fn into_iter(self) -> Self::IntoIter { self.iter() }
         }
+
+        /// A mutable view of a ket, e.g. one belonging to a basis.
+        #[derive(Debug)]
+        pub struct $KetMut<'a> {
+            pub(crate) $a: &'a mut [$A],
+            pub(crate) $b: &'a mut [$B],
+        }
+
+        impl<'a> $KetMut<'a> {
+            #[inline]
+            pub fn new($a: &'a mut [$A], $b: &'a mut [$B]) -> Self {
+                $KetMut { $a, $b }
+            }
+
+            #[inline]
+            pub fn as_ref(&self) -> $KetRef {
+                $KetRef { $a: self.$a, $b: self.$b }
+            }
+
+            #[inline]
+            pub fn $a(&self) -> &[$A] { self.$a }
+            #[inline]
+            pub fn $b(&self) -> &[$B] { self.$b }
+
+            #[inline]
+            pub fn $a_mut(&mut self) -> &mut [$A] { self.$a }
+            #[inline]
+            pub fn $b_mut(&mut self) -> &mut [$B] { self.$b }
+
+            #[inline]
+            pub fn len(&self) -> usize { self.$a.len() }
+            #[inline]
+            pub fn to_owned(&self) -> $Ket { self.as_ref().to_owned() }
+
+            /// Computes `self += alpha * x` in place.
+            ///
+            /// Panics if the lengths differ.
+            pub fn axpy<K: $AsKetRef>(&mut self, alpha: $Rect, x: K) {
+                ::errors::unwrap(self.try_axpy(alpha, x))
+            }
+        }
+
+        impl<'a> $AsKetRef for $KetMut<'a> {
+            #[inline]
+            fn as_ket_ref(&self) -> $KetRef { self.as_ref() }
+        }
+
+        /// Raw data type with no invariants, for deserialization
+        #[cfg(feature = "serde")]
+        #[derive(Deserialize)]
+        #[derive(Debug, Clone, PartialEq)]
+        pub(crate) struct Cereal {
+            pub $a: Vec<$A>,
+            pub $b: Vec<$B>,
+        }
+
+        #[cfg(feature = "serde")]
+        impl Cereal {
+            pub fn try_validate(self) -> ::errors::Result<$Ket> {
+                let Cereal { $a, $b } = self;
+                ::errors::check_components($a.len(), $b.len())?;
+                Ok($Ket { $a, $b })
+            }
+        }
+
+        forward_serde_impls!{
+            deserialize: [$Ket from Cereal::try_validate]
+        }
     };
 }
 
@@ -207,42 +305,170 @@ macro_rules! forward_serde_impls {
         #[cfg(feature = "serde")]
         impl ::serde::Serialize for $Type
         {
-            fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+            fn serialize<S>(&self, s: S) -> ::std::result::Result<S::Ok, S::Error>
             where S: ::serde::Serializer,
             { self.clone().$cereal().serialize(s) }
         }
 
+        forward_serde_impls!{
+            deserialize: [$Type from $Cereal::$validate]
+        }
+    };
+    (
+        deserialize: [$Type:ident from $Cereal:ident :: $validate:ident]
+    ) => {
         #[cfg(feature = "serde")]
         impl<'de> ::serde::Deserialize<'de> for $Type
         {
-            fn deserialize<D>(d: D) -> Result<$Type, D::Error>
+            fn deserialize<D>(d: D) -> ::std::result::Result<$Type, D::Error>
             where D: ::serde::Deserializer<'de>
             {
                 let cereal = <$Cereal as ::serde::Deserialize>::deserialize(d)?;
-                Ok(cereal.$validate())
+                cereal.$validate().map_err(<D::Error as ::serde::de::Error>::custom)
             }
         }
     };
 }
 
+fn is_permutation(perm: &[usize], len: usize) -> bool {
+    let mut seen = vec![false; len];
+    perm.len() == len && perm.iter().all(|&i| i < len && !::std::mem::replace(&mut seen[i], true))
+}
+
+// Implementation of dot_matrix, generic over the precision of the arithmetic.
+//
+// `bra` and `ket` write components `k0..k1` of a ket in rectangular form into the
+// output buffers.  The result is row-major, with real and imaginary parts.
+//
+// This is structured like a matrix multiplication.  Within a range of components,
+// the kets are taken TILE_KETS at a time and packed so that their components are
+// interleaved, and dot_tile then multiplies TILE_BRAS bras at a time against each
+// pack using independent accumulators.  Each component that is loaded thereby gets
+// used several times, and the accumulators for different kets can be updated with
+// SIMD instructions.
+fn dot_matrix_by_tiles<T, B, K>(rows: usize, cols: usize, width: usize, bra: B, ket: K) -> Vec<(T, T)>
+where
+    T: Copy + Default + ::std::ops::Add<Output=T> + ::std::ops::Sub<Output=T> + ::std::ops::Mul<Output=T>,
+    B: Fn(usize, usize, usize, &mut [T], &mut [T]),
+    K: Fn(usize, usize, usize, &mut [T], &mut [T]),
+{
+    let zero = T::default();
+    let mut out = vec![(zero, zero); rows * cols];
+    if rows == 0 || cols == 0 {
+        return out;
+    }
+
+    let mut bra_real = vec![zero; rows * DOT_BLOCK_WIDTH];
+    let mut bra_imag = vec![zero; rows * DOT_BLOCK_WIDTH];
+    let mut ket_real = [zero; DOT_BLOCK_WIDTH];
+    let mut ket_imag = [zero; DOT_BLOCK_WIDTH];
+    let mut packed_real = [[zero; TILE_KETS]; DOT_BLOCK_WIDTH];
+    let mut packed_imag = [[zero; TILE_KETS]; DOT_BLOCK_WIDTH];
+
+    for k0 in (0..width).step_by(DOT_BLOCK_WIDTH) {
+        let k1 = usize::min(k0 + DOT_BLOCK_WIDTH, width);
+        let len = k1 - k0;
+        for i in 0..rows {
+            let range = i * len..(i + 1) * len;
+            bra(i, k0, k1, &mut bra_real[range.clone()], &mut bra_imag[range]);
+        }
+
+        for j0 in (0..cols).step_by(TILE_KETS) {
+            // (a partial pack at the end is padded with zeros)
+            for t in 0..TILE_KETS {
+                if j0 + t < cols {
+                    ket(j0 + t, k0, k1, &mut ket_real[..len], &mut ket_imag[..len]);
+                } else {
+                    for x in ket_real.iter_mut().chain(&mut ket_imag) { *x = zero; }
+                }
+                for k in 0..len {
+                    packed_real[k][t] = ket_real[k];
+                    packed_imag[k][t] = ket_imag[k];
+                }
+            }
+
+            for i0 in (0..rows).step_by(TILE_BRAS) {
+                // (a partial tile at the end repeats the last bra)
+                let mut bras = [(&bra_real[..0], &bra_imag[..0]); TILE_BRAS];
+                for (t, slot) in bras.iter_mut().enumerate() {
+                    let i = usize::min(i0 + t, rows - 1);
+                    *slot = (&bra_real[i * len..(i + 1) * len], &bra_imag[i * len..(i + 1) * len]);
+                }
+
+                let (real, imag) = dot_tile(&bras, &packed_real[..len], &packed_imag[..len]);
+                for i in i0..usize::min(i0 + TILE_BRAS, rows) {
+                    for j in j0..usize::min(j0 + TILE_KETS, cols) {
+                        let x = &mut out[i * cols + j];
+                        *x = (x.0 + real[i - i0][j - j0], x.1 + imag[i - i0][j - j0]);
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+// Computes the dot products of each bra with each of the packed kets.
+//
+// (This is kept out of line because the compiler did not vectorize it once inlined.)
+#[inline(never)]
+fn dot_tile<T>(
+    bras: &[(&[T], &[T]); TILE_BRAS],
+    packed_real: &[[T; TILE_KETS]],
+    packed_imag: &[[T; TILE_KETS]],
+) -> ([[T; TILE_KETS]; TILE_BRAS], [[T; TILE_KETS]; TILE_BRAS])
+where
+    T: Copy + Default + ::std::ops::Add<Output=T> + ::std::ops::Sub<Output=T> + ::std::ops::Mul<Output=T>,
+{
+    let len = packed_real.len();
+    assert_eq!(packed_imag.len(), len);
+    for &(real, imag) in bras {
+        assert!(real.len() == len && imag.len() == len);
+    }
+
+    let zero = T::default();
+    let mut real = [[zero; TILE_KETS]; TILE_BRAS];
+    let mut imag = [[zero; TILE_KETS]; TILE_BRAS];
+    for k in 0..len {
+        let (br, bi) = (&packed_real[k], &packed_imag[k]);
+        for a in 0..TILE_BRAS {
+            let (ar, ai) = (bras[a].0[k], bras[a].1[k]);
+            for b in 0..TILE_KETS {
+                real[a][b] = real[a][b] + (ar * br[b] + ai * bi[b]);
+                imag[a][b] = imag[a][b] + (ar * bi[b] - ai * br[b]);
+            }
+        }
+    }
+    (real, imag)
+}
+
+// tile sizes for dot_matrix
+const TILE_BRAS: usize = 2;
+const TILE_KETS: usize = 4;
+const DOT_BLOCK_WIDTH: usize = 256;
+
 /// Full double-precision rectangular representation,
 /// for applications where precision matters.
 pub(crate) mod lossless {
     pub(crate) mod basis {
-        use super::ket::KetRef;
+        use super::ket::{Ket, KetRef, KetMut, AsKetRef};
+        use ::complex::lossless::Rect;
+        use ::errors::{Result, Error, check_width, unwrap};
 
         pub type Iter<'a> = Box<Iterator<Item=KetRef<'a>> + 'a>;
+        pub type IterMut<'a> = Box<Iterator<Item=KetMut<'a>> + 'a>;
 
         // invariants:
+        //  - width > 0
         //  - data.len() is divisible by 2 * width
         // contracts that aren't strictly protected as invariants:
         //  - eigenvectors SHOULD be orthogonal
         //  - eigenvectors SHOULD be normalized
         #[derive(Debug, Clone, PartialEq)]
         pub struct Basis {
-            width: usize,
+            pub(crate) width: usize,
             // reals of ket 1, then imags of ket 1, then reals of ket 2...
-            data: Vec<f64>,
+            pub(crate) data: Vec<f64>,
         }
 
         impl Basis {
@@ -251,13 +477,83 @@ pub(crate) mod lossless {
                 Cereal { data, width }.validate()
             }
 
-            // takes a tuple to be forward-compatible with
-            // a possible overload in the future for KetRef
-            pub fn insert(&mut self, (real, imag): (&[f64], &[f64])) {
-                assert_eq!(self.width, real.len());
-                assert_eq!(self.width, imag.len());
+            #[inline]
+            pub fn try_new(data: Vec<f64>, width: usize) -> Result<Basis> {
+                Cereal { data, width }.try_validate()
+            }
+
+            /// Build a basis from kets of the given width.
+            pub fn from_kets<K: AsKetRef, I: IntoIterator<Item=K>>(width: usize, kets: I) -> Basis {
+                unwrap(Basis::try_from_kets(width, kets))
+            }
+
+            pub fn try_from_kets<K: AsKetRef, I: IntoIterator<Item=K>>(width: usize, kets: I) -> Result<Basis> {
+                let mut out = Basis::try_new(vec![], width)?;
+                for ket in kets {
+                    out.try_insert(ket)?;
+                }
+                Ok(out)
+            }
+
+            // accepts anything ket-like, including a `(real, imag)` tuple of slices
+            pub fn insert<K: AsKetRef>(&mut self, ket: K) {
+                unwrap(self.try_insert(ket))
+            }
+
+            pub fn try_insert<K: AsKetRef>(&mut self, ket: K) -> Result<()> {
+                let KetRef { real, imag } = ket.as_ket_ref();
+                check_width(self.width, real.len())?;
+                check_width(self.width, imag.len())?;
                 self.data.extend_from_slice(real);
                 self.data.extend_from_slice(imag);
+                Ok(())
+            }
+
+            /// Append the kets of another basis.
+            pub fn append(&mut self, other: &Basis) {
+                unwrap(self.try_append(other))
+            }
+
+            pub fn try_append(&mut self, other: &Basis) -> Result<()> {
+                check_width(self.width, other.width)?;
+                self.data.extend_from_slice(&other.data);
+                Ok(())
+            }
+
+            /// Remove and return the ket at index `i`, shifting the ones after it.
+            pub fn remove(&mut self, i: usize) -> Ket {
+                let ket = self.ket(i).to_owned();
+                let w = self.width;
+                self.data.drain(w * 2 * i .. w * 2 * (i + 1));
+                ket
+            }
+
+            /// Keep only the first `rank` kets.  Has no effect if there are already fewer.
+            pub fn truncate(&mut self, rank: usize) {
+                self.data.truncate(2 * self.width * rank);
+            }
+
+            /// Split into the first `rank` kets and the rest.
+            ///
+            /// Panics if `rank > self.rank()`.
+            pub fn split_at(&self, rank: usize) -> (Basis, Basis) {
+                assert!(rank <= self.rank(), "split index {} exceeds rank {}", rank, self.rank());
+                let (a, b) = self.data.split_at(2 * self.width * rank);
+                let width = self.width;
+                (Basis { width, data: a.to_vec() }, Basis { width, data: b.to_vec() })
+            }
+
+            /// Collect the kets at the given indices, in order.  Indices may repeat.
+            pub fn select(&self, indices: &[usize]) -> Basis {
+                Basis::from_kets(self.width, indices.iter().map(|&i| self.ket(i)))
+            }
+
+            /// Reorder the kets, so that ket `i` of the output is ket `perm[i]` of `self`.
+            ///
+            /// Panics if `perm` is not a permutation of `0..self.rank()`.
+            pub fn permute(&self, perm: &[usize]) -> Basis {
+                assert!(::basis::is_permutation(perm, self.rank()), "not a permutation of {} kets", self.rank());
+                self.select(perm)
             }
 
             /// Number of dimensions in a ket.
@@ -280,6 +576,22 @@ pub(crate) mod lossless {
                 Box::new((0..self.rank()).map(move |i| self.ket(i)))
             }
 
+            #[inline]
+            pub fn ket_mut(&mut self, i: usize) -> KetMut {
+                let w = self.width;
+                let (real, imag) = self.data[w * 2 * i .. w * 2 * (i + 1)].split_at_mut(w);
+                KetMut { real, imag }
+            }
+
+            #[inline]
+            pub fn iter_mut(&mut self) -> IterMut {
+                let w = self.width;
+                Box::new(self.data.chunks_mut(2 * w).map(move |chunk| {
+                    let (real, imag) = chunk.split_at_mut(w);
+                    KetMut { real, imag }
+                }))
+            }
+
             pub fn lossy_compress(&self) -> ::compact::Basis {
                 use ::complex::compact::PhaseTable;
 
@@ -306,8 +618,7 @@ pub(crate) mod lossless {
             // is irrelevant, and that the change in order merely makes the (already present) opportunities
             // for parallelism more obvious.
             //
-            // (I won't bother with parallelizing this yet, since we can do *even better* than a
-            //  parallel inner loop by explicitly using rayon::join)
+            // (see `par_orthonormalize` for a version that takes advantage of this)
             //
             /// Orthonormalize a basis using the Modified Gram Schmidt method.
             pub fn orthonormalize(&self) -> Basis {
@@ -315,17 +626,297 @@ pub(crate) mod lossless {
                 for ket in self {
                     let mut ket = ket.to_owned();
                     for bra in &out {
-                        let projected = ket.projected_onto(bra);
+                        subtract_projection(&mut ket, bra);
+                    }
+                    ket = ket.into_normalized();
+                    out.insert((ket.real(), ket.imag()));
+                }
+                out
+            }
 
-                        // ket -= projected
-                        ket.real.iter_mut().zip(projected.real).for_each(|(a, b)| { *a -= b; });
-                        ket.imag.iter_mut().zip(projected.imag).for_each(|(a, b)| { *a -= b; });
+            /// Orthonormalize a basis using the Modified Gram Schmidt method,
+            /// discarding kets that are linearly dependent on those before them.
+            ///
+            /// A ket is discarded if the norm of what remains after projecting out the
+            /// previous kets is less than `tol`.  Returns an orthonormal basis for the
+            /// span of `self`, along with the indices of the discarded kets.
+            pub fn orthonormalize_span(&self, tol: f64) -> (Basis, Vec<usize>) {
+                let mut out = Basis::new(vec![], self.width);
+                let mut dropped = vec![];
+                for (i, ket) in self.iter().enumerate() {
+                    let mut ket = ket.to_owned();
+                    for bra in &out {
+                        subtract_projection(&mut ket, bra);
+                    }
+                    if ket.norm() < tol {
+                        dropped.push(i);
+                        continue;
                     }
                     ket = ket.into_normalized();
                     out.insert((ket.real(), ket.imag()));
                 }
+                (out, dropped)
+            }
+
+            /// Orthonormalize a basis using the Modified Gram Schmidt method, in parallel.
+            ///
+            /// Each ket undergoes exactly the same sequence of floating point operations
+            /// as in `orthonormalize`, so the results are identical.
+            #[cfg(feature = "rayon")]
+            pub fn par_orthonormalize(&self) -> Basis {
+                use ::rayon::prelude::*;
+
+                let mut out = Basis::new(vec![], self.width);
+                for start in (0..self.rank()).step_by(ORTHO_BLOCK_KETS) {
+                    let end = usize::min(start + ORTHO_BLOCK_KETS, self.rank());
+                    let mut block: Vec<Ket> = (start..end).map(|i| self.ket(i).to_owned()).collect();
+
+                    // The kets in a block are independent of each other with respect to
+                    // everything that has already been finished...
+                    block.par_iter_mut().for_each(|ket| {
+                        for bra in &out {
+                            subtract_projection(ket, bra);
+                        }
+                    });
+
+                    // ...leaving only a short chain of dependencies within the block.
+                    block.reverse();
+                    while let Some(ket) = block.pop() {
+                        let ket = ket.into_normalized();
+                        block.par_iter_mut().for_each(|other| subtract_projection(other, ket.as_ref()));
+                        out.insert((ket.real(), ket.imag()));
+                    }
+                }
                 out
             }
+
+            /// Computes `<self_i|other_j>` for every pair of kets.
+            ///
+            /// The output is row-major, with `self.rank()` rows and `other.rank()` columns.
+            pub fn dot_matrix(&self, other: &Basis) -> Vec<Rect> {
+                unwrap(self.try_dot_matrix(other))
+            }
+
+            /// Computes `<self_i|other_j>` for every pair of kets, failing if the widths differ.
+            pub fn try_dot_matrix(&self, other: &Basis) -> Result<Vec<Rect>> {
+                check_width(self.width, other.width)?;
+
+                let (rows, cols) = (self.rank(), other.rank());
+                let chunk = |basis: &Basis, i: usize, k0: usize, k1: usize, real: &mut [f64], imag: &mut [f64]| {
+                    let ket = basis.ket(i);
+                    real.copy_from_slice(&ket.real[k0..k1]);
+                    imag.copy_from_slice(&ket.imag[k0..k1]);
+                };
+                let out = ::basis::dot_matrix_by_tiles(
+                    rows, cols, self.width,
+                    |i, k0, k1, real, imag| chunk(self, i, k0, k1, real, imag),
+                    |j, k0, k1, real, imag| chunk(other, j, k0, k1, real, imag),
+                );
+                Ok(out.into_iter().map(|(real, imag)| Rect { real, imag }).collect())
+            }
+
+            /// Computes `<self_i|other_j><other_j|self_i>` for every pair of kets.
+            ///
+            /// The output is row-major, with `self.rank()` rows and `other.rank()` columns.
+            pub fn overlap_matrix(&self, other: &Basis) -> Vec<f64> {
+                unwrap(self.try_overlap_matrix(other))
+            }
+
+            pub fn try_overlap_matrix(&self, other: &Basis) -> Result<Vec<f64>> {
+                Ok(self.try_dot_matrix(other)?.into_iter().map(Rect::sqnorm).collect())
+            }
+
+            /// Computes the kets `|out_j> = sum_i u_ij |self_i>`.
+            ///
+            /// `matrix` is row-major, with `self.rank()` rows and `new_rank` columns.
+            /// Panics if it has the wrong length.
+            pub fn transform(&self, matrix: &[Rect], new_rank: usize) -> Basis {
+                unwrap(self.try_transform(matrix, new_rank))
+            }
+
+            pub fn try_transform(&self, matrix: &[Rect], new_rank: usize) -> Result<Basis> {
+                let (rank, w) = (self.rank(), self.width);
+                if matrix.len() != rank * new_rank {
+                    return Err(Error::MatrixLength { len: matrix.len(), shape: (rank, new_rank) });
+                }
+
+                let mut out = Basis { width: w, data: vec![0.0; 2 * w * new_rank] };
+                if rank == 0 || new_rank == 0 {
+                    return Ok(out);
+                }
+
+                // This is a matrix multiplication, blocked much like dot_matrix.  Within a range
+                // of components, the input kets are packed so that each run of TRANSFORM_LANES
+                // components is contiguous across kets.  transform_tile then sums over the input
+                // kets for TRANSFORM_TILE output kets at once, keeping the partial sums in
+                // registers rather than reloading and storing an output ket for every term.
+
+                // the coefficients of each tile of output kets, for each input ket
+                // (each is repeated across the lanes so that the kernel needs no shuffles,
+                //  and a partial tile at the end is padded with zeros)
+                let tiles: Vec<Vec<_>> = (0..new_rank).step_by(TRANSFORM_TILE).map(|j0| {
+                    (0..rank).map(|i| {
+                        let mut coeffs: (TileRuns, TileRuns) = Default::default();
+                        for t in 0..usize::min(TRANSFORM_TILE, new_rank - j0) {
+                            let c = matrix[i * new_rank + j0 + t];
+                            coeffs.0[t] = [c.real; TRANSFORM_LANES];
+                            coeffs.1[t] = [c.imag; TRANSFORM_LANES];
+                        }
+                        coeffs
+                    }).collect()
+                }).collect();
+
+                let lanes = TRANSFORM_LANES;
+                let mut packed: Vec<(Run, Run)> = vec![Default::default(); rank * TRANSFORM_BLOCK_WIDTH / lanes];
+                for k0 in (0..w).step_by(TRANSFORM_BLOCK_WIDTH) {
+                    let k1 = usize::min(k0 + TRANSFORM_BLOCK_WIDTH, w);
+                    for (i, x) in self.iter().enumerate() {
+                        let runs = x.real[k0..k1].chunks(lanes).zip(x.imag[k0..k1].chunks(lanes));
+                        for (r, (real, imag)) in runs.enumerate() {
+                            // (a partial run at the end is padded with zeros)
+                            let run = &mut packed[r * rank + i];
+                            *run = Default::default();
+                            run.0[..real.len()].copy_from_slice(real);
+                            run.1[..imag.len()].copy_from_slice(imag);
+                        }
+                    }
+
+                    for (j0, coeffs) in (0..new_rank).step_by(TRANSFORM_TILE).zip(&tiles) {
+                        for (r, k) in (k0..k1).step_by(lanes).enumerate() {
+                            let (real, imag) = transform_tile(coeffs, &packed[r * rank..(r + 1) * rank]);
+                            let len = usize::min(lanes, k1 - k);
+                            for t in 0..usize::min(TRANSFORM_TILE, new_rank - j0) {
+                                let j = j0 + t;
+                                out.data[w * (2 * j + 0) + k..][..len].copy_from_slice(&real[t][..len]);
+                                out.data[w * (2 * j + 1) + k..][..len].copy_from_slice(&imag[t][..len]);
+                            }
+                        }
+                    }
+                }
+                Ok(out)
+            }
+        }
+
+        // Computes `sum_i coeffs[i] * packed[i]` for each of the output kets in a tile,
+        // over one run of components.
+        //
+        // (This is kept out of line for the same reason as dot_tile.)
+        #[inline(never)]
+        fn transform_tile(coeffs: &[(TileRuns, TileRuns)], packed: &[(Run, Run)]) -> (TileRuns, TileRuns) {
+            assert_eq!(coeffs.len(), packed.len());
+
+            let mut real = [[0.0; TRANSFORM_LANES]; TRANSFORM_TILE];
+            let mut imag = [[0.0; TRANSFORM_LANES]; TRANSFORM_TILE];
+            for (&(cr, ci), &(xr, xi)) in coeffs.iter().zip(packed) {
+                for t in 0..TRANSFORM_TILE {
+                    for l in 0..TRANSFORM_LANES {
+                        real[t][l] += cr[t][l] * xr[l] - ci[t][l] * xi[l];
+                        imag[t][l] += cr[t][l] * xi[l] + ci[t][l] * xr[l];
+                    }
+                }
+            }
+            (real, imag)
+        }
+
+        // tile sizes for transform
+        const TRANSFORM_TILE: usize = 4;
+        const TRANSFORM_LANES: usize = 2;
+        const TRANSFORM_BLOCK_WIDTH: usize = 128;
+
+        // real or imaginary parts of a run of components, for one ket or each ket of a tile
+        type Run = [f64; TRANSFORM_LANES];
+        type TileRuns = [Run; TRANSFORM_TILE];
+
+        // number of kets orthonormalized together by par_orthonormalize
+        #[cfg(feature = "rayon")]
+        const ORTHO_BLOCK_KETS: usize = 64;
+
+        // ket -= |bra><bra|ket>
+        fn subtract_projection(ket: &mut Ket, bra: KetRef) {
+            let projected = ket.projected_onto(bra);
+            *ket -= projected;
+        }
+
+        #[test]
+        fn test_dot_matrix() {
+            let random_basis = |rank, width| {
+                let data = (0..rank * width * 2).map(|_| 0.5 - ::rand::random::<f64>()).collect();
+                Basis::new(data, width)
+            };
+            // sizes that are not multiples of the tile sizes
+            let a = random_basis(13, 1100);
+            let b = random_basis(10, 1100);
+
+            let matrix = a.dot_matrix(&b);
+            let overlaps = a.overlap_matrix(&b);
+            assert_eq!(matrix.len(), 13 * 10);
+            for (i, bra) in a.iter().enumerate() {
+                for (j, ket) in b.iter().enumerate() {
+                    let expected = bra.dot(ket);
+                    assert!((matrix[i * 10 + j] - expected).abs() < 1e-10, "({},{})", i, j);
+                    assert!((overlaps[i * 10 + j] - expected.sqnorm()).abs() < 1e-10, "({},{})", i, j);
+                }
+            }
+
+            assert_eq!(a.dot_matrix(&random_basis(0, 1100)), vec![]);
+            assert_eq!(
+                a.try_dot_matrix(&random_basis(2, 3)),
+                Err(Error::WidthMismatch { expected: 1100, actual: 3 }),
+            );
+            assert_eq!(
+                a.try_overlap_matrix(&random_basis(2, 3)),
+                Err(Error::WidthMismatch { expected: 1100, actual: 3 }),
+            );
+
+            let (a, b) = (a.lossy_compress(), b.lossy_compress());
+            let matrix = a.dot_matrix(&b);
+            for (i, bra) in a.iter().enumerate() {
+                for (j, ket) in b.iter().enumerate() {
+                    let expected = bra.dot(ket);
+                    let error = matrix[i * 10 + j] - expected;
+                    assert!(f32::hypot(error.real, error.imag) < 1e-3, "({},{})", i, j);
+                }
+            }
+        }
+
+        #[test]
+        fn test_transform() {
+            let random = || 0.5 - ::rand::random::<f64>();
+            // sizes that are not multiples of the tile sizes
+            let (width, rank, new_rank) = (1101, 5, 11);
+            let basis = Basis::new((0..2 * width * rank).map(|_| random()).collect(), width);
+            let matrix: Vec<Rect> = (0..rank * new_rank).map(|_| Rect { real: random(), imag: random() }).collect();
+
+            // <b_k|out_j> = sum_i <b_k|b_i> u_ij
+            let out = basis.transform(&matrix, new_rank);
+            assert_eq!(out.rank(), new_rank);
+            let gram = basis.dot_matrix(&basis);
+            let dots = basis.dot_matrix(&out);
+            for k in 0..rank {
+                for j in 0..new_rank {
+                    let expected: Rect = (0..rank).map(|i| gram[k * rank + i] * matrix[i * new_rank + j]).sum();
+                    assert!((dots[k * new_rank + j] - expected).abs() < 1e-10, "({},{})", k, j);
+                }
+            }
+
+            // a unitary matrix (whose rows are orthonormal) preserves orthonormality
+            let basis = Basis::new((0..2 * width * 7).map(|_| random()).collect(), width).orthonormalize();
+            let unitary = Basis::new((0..2 * 7 * 7).map(|_| random()).collect(), 7).orthonormalize();
+            let matrix: Vec<Rect> = unitary.iter().flat_map(|row| row.iter()).collect();
+            let out = basis.transform(&matrix, 7);
+            let dots = out.dot_matrix(&out);
+            for i in 0..7 {
+                for j in 0..7 {
+                    let expected = if i == j { Rect::one() } else { Rect::zero() };
+                    assert!((dots[i * 7 + j] - expected).abs() < 1e-12, "({},{})", i, j);
+                }
+            }
+
+            assert_eq!(basis.transform(&[], 0).rank(), 0);
+            assert_eq!(
+                basis.try_transform(&matrix, 10),
+                Err(Error::MatrixLength { len: 49, shape: (7, 10) }),
+            );
         }
 
         #[test]
@@ -346,6 +937,112 @@ pub(crate) mod lossless {
             }
         }
 
+        #[test]
+        fn test_orthonormalize_span() {
+            let basis = Basis::new(vec![
+                1.0, 1.0, 0.0,  0.0, 0.0, 0.0,
+                // zero
+                0.0, 0.0, 0.0,  0.0, 0.0, 0.0,
+                0.0, 1.0, 0.0,  0.0, 1.0, 0.0,
+                // a combination of kets 0 and 2, plus noise
+                1.0, 2.0, 1e-12, 0.0, 1.0, 0.0,
+                0.0, 0.0, 1.0,  0.0, 0.0, 0.0,
+            ], 3);
+
+            let (span, dropped) = basis.orthonormalize_span(1e-8);
+            assert_eq!(dropped, vec![1, 3]);
+            assert_eq!(span.rank(), 3);
+            for (i, ket) in span.iter().enumerate() {
+                for (j, bra) in span.iter().enumerate() {
+                    let expected = if i == j { 1.0 } else { 0.0 };
+                    assert!(f64::abs(ket.overlap(bra) - expected) < 1e-12, "({},{})", i, j);
+                }
+            }
+        }
+
+        #[cfg(feature = "rayon")]
+        #[test]
+        fn test_par_orthonormalize() {
+            // more than one block, and a partial block at the end
+            let (dim, num_kets) = (300, 150);
+            let data = (0..dim * num_kets * 2).map(|_| 0.5 - ::rand::random::<f64>()).collect();
+            let basis = Basis::new(data, dim);
+
+            let serial = basis.orthonormalize();
+            let parallel = basis.par_orthonormalize();
+            assert_eq!(parallel.rank(), num_kets);
+            assert_eq!(serial, parallel);
+        }
+
+        #[test]
+        fn test_editing() {
+            let ket = |x: f64| Ket::new(vec![x, 0.0], vec![0.0, x]);
+            let first_elements = |basis: &Basis| basis.iter().map(|k| k.real()[0]).collect::<Vec<_>>();
+
+            let mut basis = Basis::from_kets(2, (0..4).map(|i| ket(i as f64)));
+            basis.insert(ket(4.0).as_ref());
+            basis.insert((&[5.0, 0.0], &[0.0, 5.0]));
+            assert_eq!(first_elements(&basis), vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+
+            assert_eq!(basis.remove(1).real(), &[1.0, 0.0]);
+            basis.truncate(4);
+            assert_eq!(first_elements(&basis), vec![0.0, 2.0, 3.0, 4.0]);
+
+            let (a, b) = basis.split_at(1);
+            let mut joined = b.permute(&[2, 0, 1]);
+            joined.append(&a);
+            assert_eq!(first_elements(&joined), vec![4.0, 2.0, 3.0, 0.0]);
+            assert_eq!(first_elements(&joined.select(&[3, 3])), vec![0.0, 0.0]);
+            assert_eq!(Basis::from_kets(2, Vec::<Ket>::new()).rank(), 0);
+
+            assert_eq!(basis.try_append(&Basis::new(vec![], 3)), Err(Error::WidthMismatch { expected: 2, actual: 3 }));
+            assert!(::std::panic::catch_unwind(|| basis.permute(&[0, 0, 1, 2])).is_err());
+        }
+
+        #[test]
+        fn test_ket_mut() {
+            let mut basis = Basis::new(vec![
+                3.0, 0.0,  0.0, 4.0,
+                1.0, 0.0,  0.0, 0.0,
+            ], 2);
+            basis.ket_mut(0).normalize();
+            let first = basis.ket(0).to_owned();
+            basis.ket_mut(1).axpy(Rect::i(), &first);
+            for mut ket in basis.iter_mut() {
+                ket.scale(Rect::i());
+            }
+            assert_eq!(basis.data, vec![
+                0.0, -0.8,  0.6, 0.0,
+                -0.6, 0.0,  1.0, -0.8,
+            ]);
+
+            let mut ket = Ket::new(vec![1.0, 1.0], vec![1.0, 1.0]);
+            let ragged = KetRef::new(&[1.0, 1.0], &[1.0]);
+            assert_eq!(
+                ket.as_mut().try_axpy(Rect::one(), ragged),
+                Err(Error::ComponentMismatch { first: 2, second: 1 }),
+            );
+            assert!(::std::panic::catch_unwind(move || ket += ragged).is_err());
+        }
+
+        #[test]
+        fn test_try_new_and_insert() {
+            assert_eq!(
+                Basis::try_new(vec![0.0; 10], 3),
+                Err(Error::PartialKet { len: 10, width: 3 }),
+            );
+
+            let mut basis = Basis::try_new(vec![0.0; 12], 3).unwrap();
+            assert_eq!(basis.rank(), 2);
+            assert_eq!(
+                basis.try_insert((&[1.0, 2.0, 3.0], &[1.0, 2.0])),
+                Err(Error::WidthMismatch { expected: 3, actual: 2 }),
+            );
+            assert_eq!(basis.rank(), 2);
+            assert_eq!(basis.try_insert((&[1.0, 2.0, 3.0], &[1.0, 2.0, 3.0])), Ok(()));
+            assert_eq!(basis.rank(), 3);
+        }
+
         /// Raw data type with no invariants, for serialization
         #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[derive(Debug, Clone, PartialEq)]
@@ -356,10 +1053,17 @@ pub(crate) mod lossless {
 
         impl Cereal {
             #[inline]
-            pub fn validate(self) -> Basis {
+            pub fn validate(self) -> Basis { unwrap(self.try_validate()) }
+
+            pub fn try_validate(self) -> Result<Basis> {
                 let Cereal { width, data } = self;
-                assert_eq!(data.len() % (2 * width), 0);
-                Basis { width, data }
+                if width == 0 {
+                    return Err(Error::ZeroWidth);
+                }
+                if data.len() % (2 * width) != 0 {
+                    return Err(Error::PartialKet { len: data.len(), width });
+                }
+                Ok(Basis { width, data })
             }
         }
 
@@ -374,15 +1078,27 @@ pub(crate) mod lossless {
 
         forward_serde_impls!{
             serialize: [Basis::cereal]
-            deserialize: [Cereal::validate]
+            deserialize: [Cereal::try_validate]
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn test_deserialize_invalid() {
+            let basis = Basis::new(vec![1.0, 2.0, 3.0, 4.0], 2);
+            let json = ::serde_json::to_string(&basis).unwrap();
+            assert_eq!(::serde_json::from_str::<Basis>(&json).unwrap(), basis);
+
+            assert!(::serde_json::from_str::<Basis>(r#"{"width":0,"data":[]}"#).is_err());
+            assert!(::serde_json::from_str::<Basis>(r#"{"width":2,"data":[1.0,2.0]}"#).is_err());
         }
     }
 
     pub(crate) mod ket {
         use ::complex::lossless::Rect;
+        use ::errors::{Result, check_width, check_components, unwrap};
 
         impl_common_trash! {
-            types: [Basis, Ket, KetRef]
+            types: [Basis, Ket, KetRef, KetMut]
             traits: [AsKetRef]
             elements: [Rect { real: f64, imag: f64 }]
             muts: [real_mut, imag_mut]
@@ -392,10 +1108,22 @@ pub(crate) mod lossless {
 
         impl<'a> KetRef<'a> {
             /// Computes `<self|other>` (i.e. `self` becomes the bra)
-            #[cfg(not(feature = "faster"))]
+            #[inline]
             pub fn dot<K: AsKetRef>(self, other: K) -> Rect {
+                unwrap(self.try_dot(other))
+            }
+
+            /// Computes `<self|other>`, failing if the lengths differ.
+            pub fn try_dot<K: AsKetRef>(self, other: K) -> Result<Rect> {
                 let other = other.as_ket_ref();
-                assert_eq!(self.real.len(), other.real.len());
+                check_components(self.real.len(), self.imag.len())?;
+                check_components(other.real.len(), other.imag.len())?;
+                check_width(self.real.len(), other.real.len())?;
+                Ok(self.dot_unchecked(other))
+            }
+
+            #[cfg(not(feature = "faster"))]
+            fn dot_unchecked(self, other: KetRef) -> Rect {
                 // NOTE: I'm not sure why, but iterating through all four lists together
                 //       seems to be quite a bit faster (factor of 2) compared to taking
                 //       two lists at a time and summing their elementwise products, especially
@@ -407,14 +1135,9 @@ pub(crate) mod lossless {
             }
 
             #[cfg(feature = "faster")]
-            pub fn dot<K: AsKetRef>(self, other: K) -> Rect {
+            fn dot_unchecked(self, other: KetRef) -> Rect {
                 use ::faster::prelude::*;
 
-                let other = other.as_ket_ref();
-                assert_eq!(self.real.len(), other.real.len());
-                assert_eq!(self.real.len(), other.imag.len());
-                assert_eq!(self.real.len(), self.imag.len());
-
                 let mut acc = Rect::zero();
                 (
                     self.real.simd_iter(f64s(0.0)),
@@ -456,6 +1179,133 @@ pub(crate) mod lossless {
             }
         }
 
+        impl<'a> KetMut<'a> {
+            /// Multiplies each element by `c` in place.
+            pub fn scale(&mut self, c: Rect) {
+                for (re, im) in self.real.iter_mut().zip(self.imag.iter_mut()) {
+                    let x = Rect { real: *re, imag: *im } * c;
+                    *re = x.real;
+                    *im = x.imag;
+                }
+            }
+
+            pub fn normalize(&mut self) {
+                let norm = self.as_ref().norm();
+                for x in self.real.iter_mut() { *x /= norm; }
+                for x in self.imag.iter_mut() { *x /= norm; }
+            }
+
+            pub fn try_axpy<K: AsKetRef>(&mut self, alpha: Rect, x: K) -> Result<()> {
+                let Rect { real: ar, imag: ai } = alpha;
+                self.try_zip_assign(x, |(yr, yi), (xr, xi)| {
+                    *yr += ar * xr - ai * xi;
+                    *yi += ar * xi + ai * xr;
+                })
+            }
+
+            fn try_zip_assign<K, F>(&mut self, other: K, mut f: F) -> Result<()>
+            where
+                K: AsKetRef,
+                F: FnMut((&mut f64, &mut f64), (f64, f64)),
+            {
+                let other = other.as_ket_ref();
+                check_components(self.real.len(), self.imag.len())?;
+                check_components(other.real.len(), other.imag.len())?;
+                check_width(self.real.len(), other.real.len())?;
+                let iter = self.real.iter_mut().zip(self.imag.iter_mut()).zip(other.real.iter().zip(other.imag));
+                iter.for_each(|(y, (&xr, &xi))| f(y, (xr, xi)));
+                Ok(())
+            }
+        }
+
+        // Arithmetic on kets.
+        //
+        // Binary operators accept anything that is AsKetRef on the right hand side,
+        // and panic if the lengths differ.  Those taking an owned Ket reuse its storage.
+
+        impl<K: AsKetRef> ::std::ops::AddAssign<K> for Ket {
+            fn add_assign(&mut self, other: K) {
+                unwrap(self.as_mut().try_zip_assign(other, |(yr, yi), (xr, xi)| { *yr += xr; *yi += xi; }))
+            }
+        }
+
+        impl<K: AsKetRef> ::std::ops::SubAssign<K> for Ket {
+            fn sub_assign(&mut self, other: K) {
+                unwrap(self.as_mut().try_zip_assign(other, |(yr, yi), (xr, xi)| { *yr -= xr; *yi -= xi; }))
+            }
+        }
+
+        impl ::std::ops::MulAssign<Rect> for Ket {
+            fn mul_assign(&mut self, c: Rect) { self.as_mut().scale(c) }
+        }
+
+        impl ::std::ops::MulAssign<f64> for Ket {
+            fn mul_assign(&mut self, c: f64) {
+                for x in &mut self.real { *x *= c; }
+                for x in &mut self.imag { *x *= c; }
+            }
+        }
+
+        impl<K: AsKetRef> ::std::ops::Add<K> for Ket {
+            type Output = Ket;
+            fn add(mut self, other: K) -> Ket { self += other; self }
+        }
+
+        impl<K: AsKetRef> ::std::ops::Sub<K> for Ket {
+            type Output = Ket;
+            fn sub(mut self, other: K) -> Ket { self -= other; self }
+        }
+
+        impl ::std::ops::Mul<Rect> for Ket {
+            type Output = Ket;
+            fn mul(mut self, c: Rect) -> Ket { self *= c; self }
+        }
+
+        impl ::std::ops::Mul<f64> for Ket {
+            type Output = Ket;
+            fn mul(mut self, c: f64) -> Ket { self *= c; self }
+        }
+
+        impl ::std::ops::Neg for Ket {
+            type Output = Ket;
+            fn neg(self) -> Ket { self * -1.0 }
+        }
+
+        // borrowed kets forward to the owned implementations
+        macro_rules! forward_borrowed_ket_ops {
+            ($([$($lt:tt),*] $Type:ty;)*) => {$(
+                impl<$($lt,)* K: AsKetRef> ::std::ops::Add<K> for $Type {
+                    type Output = Ket;
+                    fn add(self, other: K) -> Ket { self.to_owned() + other }
+                }
+
+                impl<$($lt,)* K: AsKetRef> ::std::ops::Sub<K> for $Type {
+                    type Output = Ket;
+                    fn sub(self, other: K) -> Ket { self.to_owned() - other }
+                }
+
+                impl<$($lt),*> ::std::ops::Mul<Rect> for $Type {
+                    type Output = Ket;
+                    fn mul(self, c: Rect) -> Ket { self.to_owned() * c }
+                }
+
+                impl<$($lt),*> ::std::ops::Mul<f64> for $Type {
+                    type Output = Ket;
+                    fn mul(self, c: f64) -> Ket { self.to_owned() * c }
+                }
+
+                impl<$($lt),*> ::std::ops::Neg for $Type {
+                    type Output = Ket;
+                    fn neg(self) -> Ket { -self.to_owned() }
+                }
+            )*};
+        }
+
+        forward_borrowed_ket_ops! {
+            ['a] KetRef<'a>;
+            ['a] &'a Ket;
+        }
+
         #[cfg(test)]
         fn random_vector(n: usize) -> Vec<f64> {
             (0..n).map(|_| 0.5 - ::rand::random::<f64>()).collect()
@@ -470,6 +1320,30 @@ pub(crate) mod lossless {
             assert!(1.0 - f64::abs(ket.into_normalized().norm()) < 1e-12);
         }
 
+        #[test]
+        fn test_ket_ops() {
+            use ::errors::Error;
+
+            let a = Ket::new(vec![1.0, 2.0], vec![0.0, -1.0]);
+            let b = KetRef::new(&[0.5, 0.0], &[1.0, 1.0]);
+            let i = Rect::i();
+
+            let elements = |ket: Ket| ket.iter().collect::<Vec<_>>();
+
+            assert_eq!(elements(&a + b), vec![Rect { real: 1.5, imag: 1.0 }, Rect { real: 2.0, imag: 0.0 }]);
+            assert_eq!(elements(b - &a), vec![Rect { real: -0.5, imag: 1.0 }, Rect { real: -2.0, imag: 2.0 }]);
+            assert_eq!(elements(-b), elements(b * -1.0));
+            assert_eq!(elements(&a * i), elements(a.as_ref().scale(i)));
+
+            let mut c = a.clone();
+            c += b;
+            c -= b;
+            c.axpy(i, b);
+            assert_eq!(elements(c.clone()), elements(a + b * i));
+
+            assert_eq!(c.try_axpy(i, KetRef::new(&[1.0], &[1.0])), Err(Error::WidthMismatch { expected: 2, actual: 1 }));
+        }
+
         #[test]
         fn test_dot() {
             // imaginary dotted against real
@@ -505,6 +1379,23 @@ pub(crate) mod lossless {
             assert_eq!(a.dot(b), Rect { real: 0.0, imag: 5.0 });
             assert_eq!(b.dot(a), Rect { real: 0.0, imag: -5.0 });
         }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn test_serde() {
+            let ket = Ket::new(vec![1.0, 2.0], vec![3.0, 4.0]);
+            let json = ::serde_json::to_string(&ket).unwrap();
+            assert_eq!(json, ::serde_json::to_string(&ket.as_ref()).unwrap());
+
+            let back: Ket = ::serde_json::from_str(&json).unwrap();
+            assert_eq!((back.real(), back.imag()), (ket.real(), ket.imag()));
+
+            assert!(::serde_json::from_str::<Ket>(r#"{"real":[1.0],"imag":[]}"#).is_err());
+
+            let rect = Rect { real: 1.5, imag: -2.0 };
+            let json = ::serde_json::to_string(&rect).unwrap();
+            assert_eq!(::serde_json::from_str::<Rect>(&json).unwrap(), rect);
+        }
     }
 }
 
@@ -513,11 +1404,15 @@ pub(crate) mod lossless {
 /// Suitable for e.g. band uncrossing.
 pub(crate) mod compact {
     pub(crate) mod basis {
-        use super::ket::KetRef;
+        use super::ket::{Ket, KetRef, KetMut, AsKetRef};
+        use ::complex::compact::{Rect, PhaseTable};
+        use ::errors::{Result, Error, check_width, check_components, unwrap};
 
         pub type Iter<'a> = Box<Iterator<Item=KetRef<'a>> + 'a>;
+        pub type IterMut<'a> = Box<Iterator<Item=KetMut<'a>> + 'a>;
 
         // invariants:
+        //  - width > 0
         //  - abs.len() == phase.len()
         //  - abs.len() is divisible by width
         // contracts that aren't strictly protected as invariants:
@@ -526,9 +1421,9 @@ pub(crate) mod compact {
         #[derive(Debug, Clone)]
         #[derive(PartialEq)]
         pub struct Basis {
-            width: usize,
-            abs:  Vec<f32>,
-            phase: Vec<u8>,
+            pub(crate) width: usize,
+            pub(crate) abs:  Vec<f32>,
+            pub(crate) phase: Vec<u8>,
         }
 
         impl Basis {
@@ -537,13 +1432,90 @@ pub(crate) mod compact {
                 Cereal { abs, phase, width }.validate()
             }
 
-            // takes a tuple to be forward-compatible with
-            // a possible overload in the future for KetRef
-            pub fn insert(&mut self, (norm, phase): (&[f32], &[u8])) {
-                assert_eq!(self.width, norm.len());
-                assert_eq!(self.width, phase.len());
-                self.abs.extend_from_slice(norm);
+            #[inline]
+            pub fn try_new(abs: Vec<f32>, phase: Vec<u8>, width: usize) -> Result<Basis> {
+                Cereal { abs, phase, width }.try_validate()
+            }
+
+            /// Build a basis from kets of the given width.
+            pub fn from_kets<K: AsKetRef, I: IntoIterator<Item=K>>(width: usize, kets: I) -> Basis {
+                unwrap(Basis::try_from_kets(width, kets))
+            }
+
+            pub fn try_from_kets<K: AsKetRef, I: IntoIterator<Item=K>>(width: usize, kets: I) -> Result<Basis> {
+                let mut out = Basis::try_new(vec![], vec![], width)?;
+                for ket in kets {
+                    out.try_insert(ket)?;
+                }
+                Ok(out)
+            }
+
+            // accepts anything ket-like, including an `(abs, phase)` tuple of slices
+            pub fn insert<K: AsKetRef>(&mut self, ket: K) {
+                unwrap(self.try_insert(ket))
+            }
+
+            pub fn try_insert<K: AsKetRef>(&mut self, ket: K) -> Result<()> {
+                let KetRef { abs, phase } = ket.as_ket_ref();
+                check_width(self.width, abs.len())?;
+                check_width(self.width, phase.len())?;
+                self.abs.extend_from_slice(abs);
                 self.phase.extend_from_slice(phase);
+                Ok(())
+            }
+
+            /// Append the kets of another basis.
+            pub fn append(&mut self, other: &Basis) {
+                unwrap(self.try_append(other))
+            }
+
+            pub fn try_append(&mut self, other: &Basis) -> Result<()> {
+                check_width(self.width, other.width)?;
+                self.abs.extend_from_slice(&other.abs);
+                self.phase.extend_from_slice(&other.phase);
+                Ok(())
+            }
+
+            /// Remove and return the ket at index `i`, shifting the ones after it.
+            pub fn remove(&mut self, i: usize) -> Ket {
+                let ket = self.ket(i).to_owned();
+                let w = self.width;
+                self.abs.drain(w * i .. w * (i + 1));
+                self.phase.drain(w * i .. w * (i + 1));
+                ket
+            }
+
+            /// Keep only the first `rank` kets.  Has no effect if there are already fewer.
+            pub fn truncate(&mut self, rank: usize) {
+                self.abs.truncate(self.width * rank);
+                self.phase.truncate(self.width * rank);
+            }
+
+            /// Split into the first `rank` kets and the rest.
+            ///
+            /// Panics if `rank > self.rank()`.
+            pub fn split_at(&self, rank: usize) -> (Basis, Basis) {
+                assert!(rank <= self.rank(), "split index {} exceeds rank {}", rank, self.rank());
+                let (abs_a, abs_b) = self.abs.split_at(self.width * rank);
+                let (phase_a, phase_b) = self.phase.split_at(self.width * rank);
+                let width = self.width;
+                (
+                    Basis { width, abs: abs_a.to_vec(), phase: phase_a.to_vec() },
+                    Basis { width, abs: abs_b.to_vec(), phase: phase_b.to_vec() },
+                )
+            }
+
+            /// Collect the kets at the given indices, in order.  Indices may repeat.
+            pub fn select(&self, indices: &[usize]) -> Basis {
+                Basis::from_kets(self.width, indices.iter().map(|&i| self.ket(i)))
+            }
+
+            /// Reorder the kets, so that ket `i` of the output is ket `perm[i]` of `self`.
+            ///
+            /// Panics if `perm` is not a permutation of `0..self.rank()`.
+            pub fn permute(&self, perm: &[usize]) -> Basis {
+                assert!(::basis::is_permutation(perm, self.rank()), "not a permutation of {} kets", self.rank());
+                self.select(perm)
             }
 
             #[inline]
@@ -563,6 +1535,75 @@ pub(crate) mod compact {
             pub fn iter(&self) -> Iter {
                 Box::new((0..self.rank()).map(move |i| self.ket(i)))
             }
+
+            #[inline]
+            pub fn ket_mut(&mut self, i: usize) -> KetMut {
+                let w = self.width;
+                KetMut {
+                    abs:   &mut self.abs  [w * i .. w * (i + 1)],
+                    phase: &mut self.phase[w * i .. w * (i + 1)],
+                }
+            }
+
+            #[inline]
+            pub fn iter_mut(&mut self) -> IterMut {
+                let w = self.width;
+                let chunks = self.abs.chunks_mut(w).zip(self.phase.chunks_mut(w));
+                Box::new(chunks.map(|(abs, phase)| KetMut { abs, phase }))
+            }
+
+            pub fn decompress(&self) -> ::lossless::Basis {
+                let table = PhaseTable::get();
+                let mut data = Vec::with_capacity(2 * self.abs.len());
+                for ket in self.iter() {
+                    data.extend(ket.iter().map(|c| c.to_rect(table).real as f64));
+                    data.extend(ket.iter().map(|c| c.to_rect(table).imag as f64));
+                }
+                let width = self.width;
+                ::basis::lossless::basis::Cereal { width, data }.validate()
+            }
+
+            /// Computes `<self_i|other_j>` for every pair of kets.
+            ///
+            /// The output is row-major, with `self.rank()` rows and `other.rank()` columns.
+            pub fn dot_matrix(&self, other: &Basis) -> Vec<Rect> {
+                unwrap(self.try_dot_matrix(other))
+            }
+
+            /// Computes `<self_i|other_j>` for every pair of kets, failing if the widths differ.
+            pub fn try_dot_matrix(&self, other: &Basis) -> Result<Vec<Rect>> {
+                check_width(self.width, other.width)?;
+
+                // Converting each ket to rectangular form once leaves the rest of the
+                // work free of table lookups.  (see dot_matrix_by_tiles)
+                let table = PhaseTable::get();
+                let (rows, cols) = (self.rank(), other.rank());
+                let chunk = |basis: &Basis, i: usize, k0: usize, k1: usize, real: &mut [f32], imag: &mut [f32]| {
+                    let ket = basis.ket(i);
+                    for (k, (re, im)) in (k0..k1).zip(real.iter_mut().zip(imag)) {
+                        let x = ket.at(k).to_rect(table);
+                        *re = x.real;
+                        *im = x.imag;
+                    }
+                };
+                let out = ::basis::dot_matrix_by_tiles(
+                    rows, cols, self.width,
+                    |i, k0, k1, real, imag| chunk(self, i, k0, k1, real, imag),
+                    |j, k0, k1, real, imag| chunk(other, j, k0, k1, real, imag),
+                );
+                Ok(out.into_iter().map(|(real, imag)| Rect { real, imag }).collect())
+            }
+
+            /// Computes `<self_i|other_j><other_j|self_i>` for every pair of kets.
+            ///
+            /// The output is row-major, with `self.rank()` rows and `other.rank()` columns.
+            pub fn overlap_matrix(&self, other: &Basis) -> Vec<f32> {
+                unwrap(self.try_overlap_matrix(other))
+            }
+
+            pub fn try_overlap_matrix(&self, other: &Basis) -> Result<Vec<f32>> {
+                Ok(self.try_dot_matrix(other)?.into_iter().map(Rect::sqnorm).collect())
+            }
         }
 
         /// Raw data type with no invariants, for serialization
@@ -576,11 +1617,18 @@ pub(crate) mod compact {
 
         impl Cereal {
             #[inline]
-            pub fn validate(self) -> Basis {
+            pub fn validate(self) -> Basis { unwrap(self.try_validate()) }
+
+            pub fn try_validate(self) -> Result<Basis> {
                 let Cereal { width, abs, phase } = self;
-                assert_eq!(abs.len(), phase.len());
-                assert_eq!(abs.len() % width, 0);
-                Basis { width, abs, phase }
+                if width == 0 {
+                    return Err(Error::ZeroWidth);
+                }
+                check_components(abs.len(), phase.len())?;
+                if abs.len() % width != 0 {
+                    return Err(Error::PartialKet { len: abs.len(), width });
+                }
+                Ok(Basis { width, abs, phase })
             }
         }
 
@@ -594,15 +1642,65 @@ pub(crate) mod compact {
 
         forward_serde_impls!{
             serialize: [Basis::cereal]
-            deserialize: [Cereal::validate]
+            deserialize: [Cereal::try_validate]
+        }
+
+        #[test]
+        fn test_editing() {
+            let mut basis = Basis::from_kets(1, vec![(&[1.0], &[10]), (&[2.0], &[20])]);
+            basis.insert(basis.ket(0).to_owned());
+            basis.append(&basis.clone());
+            assert_eq!(basis.remove(0).phase(), &[10]);
+            basis.truncate(4);
+
+            let (a, b) = basis.permute(&[1, 0, 2, 3]).split_at(1);
+            assert_eq!((a.abs, a.phase), (vec![1.0], vec![10]));
+            assert_eq!((b.abs, b.phase), (vec![2.0, 1.0, 2.0], vec![20, 10, 20]));
+        }
+
+        #[test]
+        fn test_ket_mut() {
+            use ::complex::compact::Polar;
+
+            let mut basis = Basis::new(vec![3.0, 4.0, 1.0, 0.0], vec![0, 0, 64, 0], 2);
+            basis.ket_mut(0).normalize();
+            basis.ket_mut(1).scale(Polar { abs: 2.0, phase: 128 });
+            assert_eq!(basis.abs, vec![0.6, 0.8, 2.0, 0.0]);
+            assert_eq!(basis.phase, vec![0, 0, 192, 128]);
+
+            let ket = basis.ket(1).to_owned();
+            for mut k in basis.iter_mut().skip(1) {
+                k.axpy(Rect::one(), &ket);
+            }
+            assert!((basis.abs[2] - 4.0).abs() < 1e-6);
+            assert_eq!(basis.phase[2], 192);
+
+            let ragged = KetRef::new(&[1.0, 1.0], &[0]);
+            assert_eq!(
+                basis.ket_mut(0).try_axpy(Rect::one(), ragged),
+                Err(Error::ComponentMismatch { first: 2, second: 1 }),
+            );
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn test_deserialize_invalid() {
+            let basis = Basis::new(vec![1.0, 0.5], vec![0, 64], 2);
+            let json = ::serde_json::to_string(&basis).unwrap();
+            assert_eq!(::serde_json::from_str::<Basis>(&json).unwrap(), basis);
+
+            assert!(::serde_json::from_str::<Basis>(r#"{"width":0,"abs":[],"phase":[]}"#).is_err());
+            assert!(::serde_json::from_str::<Basis>(r#"{"width":2,"abs":[1.0,0.5],"phase":[0]}"#).is_err());
+            assert!(::serde_json::from_str::<Basis>(r#"{"width":2,"abs":[1.0],"phase":[0]}"#).is_err());
         }
     }
 
     pub(crate) mod ket {
         use ::complex::compact::{Rect, Polar, PhaseTable};
+        use ::errors::{Result, check_width, check_components, unwrap};
 
         impl_common_trash! {
-            types: [Basis, Ket, KetRef]
+            types: [Basis, Ket, KetRef, KetMut]
             traits: [AsKetRef]
             elements: [Polar { abs: f32, phase: u8 }]
             muts: [abs_mut, phase_mut]
@@ -611,13 +1709,25 @@ pub(crate) mod compact {
         }
 
         impl<'a> KetRef<'a> {
+            /// Computes `<self|other>` (i.e. `self` becomes the bra)
+            #[inline]
             pub fn dot<K: AsKetRef>(self, other: K) -> Rect {
+                unwrap(self.try_dot(other))
+            }
+
+            /// Computes `<self|other>`, failing if the lengths differ.
+            pub fn try_dot<K: AsKetRef>(self, other: K) -> Result<Rect> {
                 let other = other.as_ket_ref();
-                assert_eq!(self.abs.len(), other.abs.len());
+                check_components(self.abs.len(), self.phase.len())?;
+                check_components(other.abs.len(), other.phase.len())?;
+                check_width(self.abs.len(), other.abs.len())?;
+
                 let table = PhaseTable::get();
-                (0..self.abs.len())
-                    .map(|i| (self.at(i).conj() * other.at(i)).to_rect(table))
-                    .fold(Rect::zero(), |a, b| a + b)
+                Ok({
+                    (0..self.abs.len())
+                        .map(|i| (self.at(i).conj() * other.at(i)).to_rect(table))
+                        .fold(Rect::zero(), |a, b| a + b)
+                })
             }
 
             pub fn sqnorm(&self) -> f32 {
@@ -631,5 +1741,56 @@ pub(crate) mod compact {
                 self
             }
         }
+
+        impl<'a> KetMut<'a> {
+            /// Multiplies each element by `c` in place.
+            pub fn scale(&mut self, c: Polar) {
+                for x in self.abs.iter_mut() { *x *= c.abs; }
+                for x in self.phase.iter_mut() { *x = x.wrapping_add(c.phase); }
+            }
+
+            pub fn normalize(&mut self) {
+                let norm = self.as_ref().norm();
+                for x in self.abs.iter_mut() { *x /= norm; }
+            }
+
+            /// Each element of the result is rounded once.
+            pub fn try_axpy<K: AsKetRef>(&mut self, alpha: Rect, x: K) -> Result<()> {
+                let table = PhaseTable::get();
+                let x = x.as_ket_ref();
+                check_components(self.abs.len(), self.phase.len())?;
+                check_components(x.abs.len(), x.phase.len())?;
+                check_width(self.abs.len(), x.abs.len())?;
+                let iter = self.abs.iter_mut().zip(self.phase.iter_mut()).zip(x.iter());
+                for ((abs, phase), x) in iter {
+                    let y = Polar { abs: *abs, phase: *phase }.to_rect(table) + alpha * x.to_rect(table);
+                    let Polar { abs: new_abs, phase: new_phase } = y.to_polar(table);
+                    *abs = new_abs;
+                    *phase = new_phase;
+                }
+                Ok(())
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn test_serde() {
+            let ket = Ket::new(vec![1.0, 0.5], vec![0, 192]);
+            let json = ::serde_json::to_string(&ket).unwrap();
+            assert_eq!(json, ::serde_json::to_string(&ket.as_ref()).unwrap());
+
+            let back: Ket = ::serde_json::from_str(&json).unwrap();
+            assert_eq!((back.abs(), back.phase()), (ket.abs(), ket.phase()));
+
+            assert!(::serde_json::from_str::<Ket>(r#"{"abs":[1.0,0.5],"phase":[0]}"#).is_err());
+
+            let rect = Rect { real: 1.5, imag: -2.0 };
+            let json = ::serde_json::to_string(&rect).unwrap();
+            assert_eq!(::serde_json::from_str::<Rect>(&json).unwrap(), rect);
+
+            let polar = Polar { abs: 1.5, phase: 64 };
+            let json = ::serde_json::to_string(&polar).unwrap();
+            assert_eq!(::serde_json::from_str::<Polar>(&json).unwrap(), polar);
+        }
     }
 }
@@ -1,4 +1,15 @@
 
+macro_rules! rect_assign_impls {
+    ($($Trait:ident::$method:ident via $Op:ident::$op:ident for $Rhs:ty;)*) => {$(
+        impl ::std::ops::$Trait<$Rhs> for Rect {
+            #[inline(always)]
+            fn $method(&mut self, other: $Rhs) {
+                *self = ::std::ops::$Op::$op(*self, other);
+            }
+        }
+    )*};
+}
+
 macro_rules! rect_common_impls {
     ($t:ty) => {
         impl Rect {
@@ -17,6 +28,11 @@ macro_rules! rect_common_impls {
                 }
             }
 
+            #[inline]
+            pub fn from_polar(abs: $t, radians: $t) -> Rect {
+                Rect::from_phase(radians) * abs
+            }
+
             #[inline(always)]
             pub fn sqnorm(self) -> $t { self.real * self.real + self.imag * self.imag }
             #[inline]
@@ -29,6 +45,55 @@ macro_rules! rect_common_impls {
                 }
             }
 
+            /// Phase angle in radians, in `(-pi, pi]`.
+            #[inline]
+            pub fn arg(self) -> $t { self.imag.atan2(self.real) }
+
+            #[inline]
+            pub fn recip(self) -> Rect { self.conj() / self.sqnorm() }
+
+            #[inline]
+            pub fn exp(self) -> Rect { Rect::from_polar(self.real.exp(), self.imag) }
+
+            /// Principal branch of the natural logarithm.
+            #[inline]
+            pub fn ln(self) -> Rect {
+                Rect {
+                    real: self.abs().ln(),
+                    imag: self.arg(),
+                }
+            }
+
+            /// Principal square root, with a nonnegative real part.
+            pub fn sqrt(self) -> Rect {
+                let abs = self.abs();
+                let real = ((abs + self.real) / 2.0).max(0.0).sqrt();
+                let imag = ((abs - self.real) / 2.0).max(0.0).sqrt();
+                let imag = if self.imag < 0.0 { -imag } else { imag };
+                Rect { real, imag }
+            }
+
+            /// Integer power by repeated squaring, so that e.g. `i.powi(2)` is exactly `-1`.
+            pub fn powi(self, n: i32) -> Rect {
+                let mut base = if n < 0 { self.recip() } else { self };
+                let mut n = (n as i64).abs();
+                let mut acc = Rect::one();
+                while n > 0 {
+                    if n & 1 == 1 {
+                        acc *= base;
+                    }
+                    base *= base;
+                    n >>= 1;
+                }
+                acc
+            }
+
+            /// Real power, using the principal branch.
+            #[inline]
+            pub fn powf(self, exp: $t) -> Rect {
+                Rect::from_polar(self.abs().powf(exp), self.arg() * exp)
+            }
+
             #[inline]
             pub fn lexical_cmp(self, other: Rect) -> Option<::std::cmp::Ordering> {
                 (self.real, self.imag).partial_cmp(&(other.real, other.imag))
@@ -75,6 +140,95 @@ macro_rules! rect_common_impls {
                 }
             }
         }
+
+        impl ::std::ops::Div<Rect> for Rect {
+            type Output = Rect;
+
+            #[inline]
+            fn div(self, other: Rect) -> Rect {
+                (self * other.conj()) / other.sqnorm()
+            }
+        }
+
+        impl ::std::ops::Neg for Rect {
+            type Output = Rect;
+
+            #[inline(always)]
+            fn neg(self) -> Rect {
+                Rect {
+                    real: -self.real,
+                    imag: -self.imag,
+                }
+            }
+        }
+
+        impl ::std::ops::Mul<$t> for Rect {
+            type Output = Rect;
+
+            #[inline(always)]
+            fn mul(self, other: $t) -> Rect {
+                Rect {
+                    real: self.real * other,
+                    imag: self.imag * other,
+                }
+            }
+        }
+
+        impl ::std::ops::Mul<Rect> for $t {
+            type Output = Rect;
+
+            #[inline(always)]
+            fn mul(self, other: Rect) -> Rect { other * self }
+        }
+
+        impl ::std::ops::Div<$t> for Rect {
+            type Output = Rect;
+
+            #[inline(always)]
+            fn div(self, other: $t) -> Rect {
+                Rect {
+                    real: self.real / other,
+                    imag: self.imag / other,
+                }
+            }
+        }
+
+        rect_assign_impls!{
+            AddAssign::add_assign via Add::add for Rect;
+            SubAssign::sub_assign via Sub::sub for Rect;
+            MulAssign::mul_assign via Mul::mul for Rect;
+            DivAssign::div_assign via Div::div for Rect;
+            MulAssign::mul_assign via Mul::mul for $t;
+            DivAssign::div_assign via Div::div for $t;
+        }
+
+        impl ::std::iter::Sum<Rect> for Rect {
+            #[inline]
+            fn sum<I: Iterator<Item=Rect>>(iter: I) -> Rect {
+                iter.fold(Rect::zero(), |a, b| a + b)
+            }
+        }
+
+        impl<'a> ::std::iter::Sum<&'a Rect> for Rect {
+            #[inline]
+            fn sum<I: Iterator<Item=&'a Rect>>(iter: I) -> Rect {
+                iter.cloned().sum()
+            }
+        }
+
+        impl ::std::iter::Product<Rect> for Rect {
+            #[inline]
+            fn product<I: Iterator<Item=Rect>>(iter: I) -> Rect {
+                iter.fold(Rect::one(), |a, b| a * b)
+            }
+        }
+
+        impl<'a> ::std::iter::Product<&'a Rect> for Rect {
+            #[inline]
+            fn product<I: Iterator<Item=&'a Rect>>(iter: I) -> Rect {
+                iter.cloned().product()
+            }
+        }
     };
 }
 
@@ -82,6 +236,7 @@ pub(crate) mod lossless {
     // N.B. PartialOrd is very deliberately not implemented because
     //      it could be a huge footgun. Generic code will have to use
     //      function-based APIs and `lexical_cmp`.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     #[derive(Debug, Copy, Clone, PartialEq)]
     pub struct Rect {
         pub real: f64,
@@ -89,6 +244,31 @@ pub(crate) mod lossless {
     }
 
     rect_common_impls!(f64);
+
+    #[test]
+    fn test_rect_algebra() {
+        let close = |a: Rect, b: Rect| (a - b).abs() < 1e-12;
+        let a = Rect { real: 1.5, imag: -2.0 };
+        let b = Rect { real: -0.25, imag: 3.0 };
+
+        assert!(close(a / b * b, a));
+        assert!(close(a * a.recip(), Rect::one()));
+        assert!(close(a.ln().exp(), a));
+        assert!(close(a.sqrt() * a.sqrt(), a));
+        assert!(close(a.powi(3), a * a * a));
+        assert!(close(a.powi(-2), (a * a).recip()));
+        assert!(close(a.powf(0.5), a.sqrt()));
+        assert!(close(Rect::from_polar(a.abs(), a.arg()), a));
+        assert_eq!(Rect::i().powi(2), -Rect::one());
+        assert_eq!(Rect { real: -4.0, imag: 0.0 }.sqrt(), Rect { real: 0.0, imag: 2.0 });
+
+        let mut c = a;
+        c *= 2.0;
+        c -= a;
+        assert_eq!(c, a);
+        assert_eq!(vec![a, b, -b].into_iter().sum::<Rect>(), a);
+        assert_eq!([a, Rect::one()].iter().product::<Rect>(), a);
+    }
 }
 
 pub(crate) mod compact {
@@ -97,12 +277,14 @@ pub(crate) mod compact {
     // N.B. PartialOrd is very deliberately not implemented because
     //      it could be a huge footgun. Generic code will have to use
     //      function-based APIs and `lexical_cmp`.
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     #[derive(Debug, Copy, Clone, PartialEq)]
     pub struct Rect {
         pub real: f32,
         pub imag: f32,
     }
 
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     #[derive(Debug, Copy, Clone, PartialEq)]
     pub struct Polar {
         pub abs: f32,
@@ -196,4 +378,15 @@ pub(crate) mod compact {
     }
 
     rect_common_impls!(f32);
+
+    impl Rect {
+        /// Round to the nearest representable `Polar`.
+        #[inline]
+        pub fn to_polar(self, table: &PhaseTable) -> Polar {
+            Polar {
+                abs: self.abs(),
+                phase: table.nearest_phase(self.arg() as f64),
+            }
+        }
+    }
 }
@@ -0,0 +1,110 @@
+//! The error type for fallible (`try_`) operations.
+
+use ::std::fmt;
+
+/// Shorthand for results of fallible operations in this crate.
+pub type Result<T> = ::std::result::Result<T, Error>;
+
+/// Reasons why data cannot be used to form a ket or basis,
+/// or why two of them cannot be combined.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// A ket did not have the length required by a basis or by another ket.
+    WidthMismatch { expected: usize, actual: usize },
+    /// The parallel component arrays of a ket or basis
+    /// (e.g. `real` and `imag`, or `abs` and `phase`) had different lengths.
+    ComponentMismatch { first: usize, second: usize },
+    /// Flat basis data did not contain a whole number of kets.
+    PartialKet { len: usize, width: usize },
+    /// A basis was given a width of zero.
+    ZeroWidth,
+    /// Two bases that should have had the same number of kets did not.
+    RankMismatch { expected: usize, actual: usize },
+    /// Two groupings of kets that should have had the same number of groups did not.
+    GroupCountMismatch { first: usize, second: usize },
+    /// Matrices that should have had the same shape did not.
+    ShapeMismatch { first: (usize, usize), second: (usize, usize) },
+    /// A flat row-major matrix did not have the length required by its shape.
+    MatrixLength { len: usize, shape: (usize, usize) },
+    /// A group label was not less than the number of kets being grouped.
+    GroupLabel { label: usize, count: usize },
+    /// The kets of a basis were linearly dependent, or too nearly so, as judged
+    /// by the smallest and largest eigenvalues of their overlap matrix.
+    LinearlyDependent { smallest: f64, largest: f64 },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::WidthMismatch { expected, actual } => {
+                write!(f, "ket width mismatch: expected {}, got {}", expected, actual)
+            },
+            Error::ComponentMismatch { first, second } => {
+                write!(f, "component arrays have different lengths: {} vs {}", first, second)
+            },
+            Error::PartialKet { len, width } => {
+                write!(f, "data of length {} does not hold a whole number of kets of width {}", len, width)
+            },
+            Error::ZeroWidth => write!(f, "basis width must be nonzero"),
+            Error::RankMismatch { expected, actual } => {
+                write!(f, "basis rank mismatch: expected {}, got {}", expected, actual)
+            },
+            Error::GroupCountMismatch { first, second } => {
+                write!(f, "groupings have different numbers of groups: {} vs {}", first, second)
+            },
+            Error::ShapeMismatch { first, second } => {
+                write!(f, "matrix shape mismatch: {:?} vs {:?}", first, second)
+            },
+            Error::MatrixLength { len, shape: (rows, cols) } => {
+                write!(f, "data of length {} cannot form a {}x{} matrix", len, rows, cols)
+            },
+            Error::GroupLabel { label, count } => {
+                write!(f, "group label {} is out of range for {} kets", label, count)
+            },
+            Error::LinearlyDependent { smallest, largest } => {
+                write!(f, "kets are linearly dependent: overlap eigenvalues range from {:e} to {:e}", smallest, largest)
+            },
+        }
+    }
+}
+
+impl ::std::error::Error for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::WidthMismatch { .. } => "ket width mismatch",
+            Error::ComponentMismatch { .. } => "component arrays have different lengths",
+            Error::PartialKet { .. } => "data does not hold a whole number of kets",
+            Error::ZeroWidth => "basis width must be nonzero",
+            Error::RankMismatch { .. } => "basis rank mismatch",
+            Error::GroupCountMismatch { .. } => "groupings have different numbers of groups",
+            Error::ShapeMismatch { .. } => "matrix shape mismatch",
+            Error::MatrixLength { .. } => "data has the wrong length for the matrix shape",
+            Error::GroupLabel { .. } => "group label out of range",
+            Error::LinearlyDependent { .. } => "kets are linearly dependent",
+        }
+    }
+}
+
+/// Require two lengths to be equal.
+#[inline]
+pub(crate) fn check_width(expected: usize, actual: usize) -> Result<()> {
+    if expected == actual { Ok(()) } else { Err(Error::WidthMismatch { expected, actual }) }
+}
+
+/// Require two bases to have the same number of kets.
+#[inline]
+pub(crate) fn check_rank(expected: usize, actual: usize) -> Result<()> {
+    if expected == actual { Ok(()) } else { Err(Error::RankMismatch { expected, actual }) }
+}
+
+/// Require two parallel component arrays to have equal lengths.
+#[inline]
+pub(crate) fn check_components(first: usize, second: usize) -> Result<()> {
+    if first == second { Ok(()) } else { Err(Error::ComponentMismatch { first, second }) }
+}
+
+/// Used to implement the panicking counterparts of `try_` methods.
+#[inline]
+pub(crate) fn unwrap<T>(r: Result<T>) -> T {
+    r.unwrap_or_else(|e| panic!("{}", e))
+}
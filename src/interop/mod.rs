@@ -0,0 +1,10 @@
+//! Conversions to and from the types of other crates, each behind a cargo feature.
+
+#[cfg(feature = "num-complex")]
+mod num_complex;
+
+#[cfg(feature = "ndarray")]
+mod ndarray;
+
+#[cfg(feature = "nalgebra")]
+mod nalgebra;
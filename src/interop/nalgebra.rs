@@ -0,0 +1,56 @@
+use ::nalgebra::{Complex, DMatrix, DVector};
+use ::errors::Result;
+use ::lossless::{Basis, Ket, KetRef};
+
+impl Basis {
+    /// Produce a `width x rank` matrix whose columns are the kets.
+    pub fn to_dmatrix(&self) -> DMatrix<Complex<f64>> {
+        DMatrix::from_fn(self.width(), self.rank(), |r, c| {
+            let ket = self.ket(c);
+            Complex::new(ket.real[r], ket.imag[r])
+        })
+    }
+
+    /// Build a basis from the columns of a `width x rank` matrix.
+    pub fn from_dmatrix(matrix: &DMatrix<Complex<f64>>) -> Result<Basis> {
+        let (width, rank) = matrix.shape();
+        let mut data = Vec::with_capacity(2 * width * rank);
+        for column in matrix.column_iter() {
+            data.extend(column.iter().map(|c| c.re));
+            data.extend(column.iter().map(|c| c.im));
+        }
+        Basis::try_new(data, width)
+    }
+}
+
+impl<'a> KetRef<'a> {
+    pub fn to_dvector(&self) -> DVector<Complex<f64>> {
+        DVector::from_fn(self.len(), |r, _| Complex::new(self.real[r], self.imag[r]))
+    }
+}
+
+impl Ket {
+    #[inline]
+    pub fn to_dvector(&self) -> DVector<Complex<f64>> { self.as_ref().to_dvector() }
+
+    pub fn from_dvector(vector: &DVector<Complex<f64>>) -> Ket {
+        vector.iter().map(|c| (c.re, c.im)).collect()
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    let basis = Basis::new(vec![
+        1.0, 2.0, 3.0,  -1.0, -2.0, -3.0,
+        4.0, 5.0, 6.0,  -4.0, -5.0, -6.0,
+    ], 3);
+    let matrix = basis.to_dmatrix();
+    assert_eq!(matrix.shape(), (3, 2));
+    assert_eq!(matrix[(1, 1)], Complex::new(5.0, -5.0));
+    assert_eq!(Basis::from_dmatrix(&matrix).unwrap(), basis);
+
+    let vector = basis.ket(0).to_dvector();
+    assert_eq!(vector, matrix.column(0).clone_owned());
+    let ket = Ket::from_dvector(&vector);
+    assert_eq!((ket.real(), ket.imag()), (basis.ket(0).real(), basis.ket(0).imag()));
+}
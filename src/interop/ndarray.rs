@@ -0,0 +1,79 @@
+use ::ndarray::{ArrayBase, ArrayView1, ArrayView2, Data, Ix2, ShapeBuilder};
+use ::errors::{Result, Error};
+use ::lossless::{Basis, Ket, KetRef};
+
+impl Basis {
+    /// View the real parts as a `rank x width` matrix, without copying.
+    pub fn real_view(&self) -> ArrayView2<f64> { self.component_view(0) }
+
+    /// View the imaginary parts as a `rank x width` matrix, without copying.
+    pub fn imag_view(&self) -> ArrayView2<f64> { self.component_view(1) }
+
+    fn component_view(&self, which: usize) -> ArrayView2<f64> {
+        let (rank, width) = (self.rank(), self.width());
+        if rank == 0 {
+            return ArrayView2::from_shape((0, width), &[]).expect("(BUG!) bad shape");
+        }
+        // each row is a contiguous run of `width` elements, and rows lie
+        // `2 * width` apart because the real and imag blocks alternate
+        let shape = (rank, width).strides((2 * width, 1));
+        ArrayView2::from_shape(shape, &self.data[which * width..]).expect("(BUG!) bad strides")
+    }
+
+    /// Build a basis from `rank x width` matrices of real and imaginary parts.
+    pub fn from_arrays<S, T>(real: &ArrayBase<S, Ix2>, imag: &ArrayBase<T, Ix2>) -> Result<Basis>
+    where
+        S: Data<Elem=f64>,
+        T: Data<Elem=f64>,
+    {
+        if real.dim() != imag.dim() {
+            return Err(Error::ShapeMismatch { first: real.dim(), second: imag.dim() });
+        }
+
+        let (rank, width) = real.dim();
+        let mut data = Vec::with_capacity(2 * rank * width);
+        for (real, imag) in real.outer_iter().zip(imag.outer_iter()) {
+            data.extend(real.iter());
+            data.extend(imag.iter());
+        }
+        Basis::try_new(data, width)
+    }
+}
+
+impl<'a> KetRef<'a> {
+    #[inline]
+    pub fn real_view(&self) -> ArrayView1<'a, f64> { ArrayView1::from(self.real) }
+    #[inline]
+    pub fn imag_view(&self) -> ArrayView1<'a, f64> { ArrayView1::from(self.imag) }
+}
+
+impl Ket {
+    #[inline]
+    pub fn real_view(&self) -> ArrayView1<f64> { ArrayView1::from(&self.real[..]) }
+    #[inline]
+    pub fn imag_view(&self) -> ArrayView1<f64> { ArrayView1::from(&self.imag[..]) }
+}
+
+#[test]
+fn test_views() {
+    use ::ndarray::arr2;
+
+    let basis = Basis::new(vec![
+        1.0, 2.0, 3.0,  -1.0, -2.0, -3.0,
+        4.0, 5.0, 6.0,  -4.0, -5.0, -6.0,
+    ], 3);
+    assert_eq!(basis.real_view(), arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]));
+    assert_eq!(basis.imag_view(), -arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]));
+    assert_eq!(basis.ket(1).imag_view(), basis.imag_view().row(1));
+
+    let back = Basis::from_arrays(&basis.real_view(), &basis.imag_view()).unwrap();
+    assert_eq!(back, basis);
+
+    let empty = Basis::new(vec![], 3);
+    assert_eq!(empty.real_view().dim(), (0, 3));
+
+    assert_eq!(
+        Basis::from_arrays(&basis.real_view(), &basis.imag_view().t()),
+        Err(Error::ShapeMismatch { first: (2, 3), second: (3, 2) }),
+    );
+}
@@ -0,0 +1,66 @@
+use ::num_complex::Complex;
+use ::complex::compact::PhaseTable;
+use ::{lossless, compact};
+
+impl From<lossless::Rect> for Complex<f64> {
+    #[inline(always)]
+    fn from(lossless::Rect { real, imag }: lossless::Rect) -> Self { Complex::new(real, imag) }
+}
+
+impl From<Complex<f64>> for lossless::Rect {
+    #[inline(always)]
+    fn from(Complex { re, im }: Complex<f64>) -> Self { lossless::Rect { real: re, imag: im } }
+}
+
+impl From<compact::Rect> for Complex<f32> {
+    #[inline(always)]
+    fn from(compact::Rect { real, imag }: compact::Rect) -> Self { Complex::new(real, imag) }
+}
+
+impl From<Complex<f32>> for compact::Rect {
+    #[inline(always)]
+    fn from(Complex { re, im }: Complex<f32>) -> Self { compact::Rect { real: re, imag: im } }
+}
+
+impl<'a> lossless::KetRef<'a> {
+    /// Iterate over the components as `num_complex` values.
+    #[inline]
+    pub fn iter_complex(&self) -> Box<Iterator<Item=Complex<f64>> + 'a> {
+        Box::new(self.iter().map(Complex::from))
+    }
+}
+
+impl lossless::Ket {
+    #[inline]
+    pub fn from_complex(data: &[Complex<f64>]) -> lossless::Ket {
+        data.iter().map(|&c| lossless::Rect::from(c)).collect()
+    }
+
+    /// Iterate over the components as `num_complex` values.
+    #[inline]
+    pub fn iter_complex<'a>(&'a self) -> Box<Iterator<Item=Complex<f64>> + 'a> { self.as_ref().iter_complex() }
+}
+
+impl<'a> compact::KetRef<'a> {
+    /// Iterate over the components as `num_complex` values.
+    #[inline]
+    pub fn iter_complex(&self) -> Box<Iterator<Item=Complex<f32>> + 'a> {
+        let table = PhaseTable::get();
+        Box::new(self.iter().map(move |c| Complex::from(c.to_rect(table))))
+    }
+}
+
+impl compact::Ket {
+    /// Iterate over the components as `num_complex` values.
+    #[inline]
+    pub fn iter_complex<'a>(&'a self) -> Box<Iterator<Item=Complex<f32>> + 'a> { self.as_ref().iter_complex() }
+}
+
+#[test]
+fn test_round_trip() {
+    let data = vec![Complex::new(1.0, -2.0), Complex::new(0.5, 3.0)];
+    let ket = lossless::Ket::from_complex(&data);
+    assert_eq!(ket.real(), &[1.0, 0.5][..]);
+    assert_eq!(ket.imag(), &[-2.0, 3.0][..]);
+    assert_eq!(ket.iter_complex().collect::<Vec<_>>(), data);
+}
@@ -5,11 +5,31 @@ extern crate serde;
 extern crate lazy_static;
 #[cfg(test)]
 extern crate rand;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
 #[cfg(feature = "faster")]
 extern crate faster;
+#[cfg(feature = "num-complex")]
+extern crate num_complex;
+#[cfg(feature = "ndarray")]
+extern crate ndarray;
+#[cfg(feature = "nalgebra")]
+extern crate nalgebra;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 
 pub(crate) mod basis;
 pub(crate) mod complex;
+pub mod errors;
+pub(crate) mod interop;
+pub(crate) mod linalg;
+pub(crate) mod matching;
+pub(crate) mod orthonormal;
+pub(crate) mod phase;
+pub(crate) mod projection;
+
+// (`Result` stays in `errors`, where it cannot shadow the prelude for glob imports)
+pub use errors::Error;
 
 // actual public API, employing the "pick your parallel namespace" model
 // frequently employed by e.g. parsing libraries when they want to
@@ -27,12 +47,23 @@ pub mod lossless {
 
     pub use ::basis::lossless::basis::Basis;
     pub use ::basis::lossless::basis::Iter as BasisIter;
+    pub use ::basis::lossless::basis::IterMut as BasisIterMut;
 
     pub use ::basis::lossless::ket::Ket;
     pub use ::basis::lossless::ket::KetRef;
+    pub use ::basis::lossless::ket::KetMut;
     pub use ::basis::lossless::ket::AsKetRef;
     pub use ::basis::lossless::ket::Iter as KetIter;
     pub use ::basis::lossless::ket::IntoIter as KetIntoIter;
+
+    pub use ::matching::Matching;
+    pub use ::matching::GroupMatching;
+    pub use ::matching::BandPath;
+    pub use ::matching::PathLink;
+
+    pub use ::orthonormal::Reorthonormalization;
+
+    pub use ::phase::PhaseConvention;
 }
 
 pub mod compact {
@@ -41,10 +72,19 @@ pub mod compact {
 
     pub use ::basis::compact::basis::Basis;
     pub use ::basis::compact::basis::Iter as BasisIter;
+    pub use ::basis::compact::basis::IterMut as BasisIterMut;
 
     pub use ::basis::compact::ket::Ket;
     pub use ::basis::compact::ket::KetRef;
+    pub use ::basis::compact::ket::KetMut;
     pub use ::basis::compact::ket::AsKetRef;
     pub use ::basis::compact::ket::Iter as KetIter;
     pub use ::basis::compact::ket::IntoIter as KetIntoIter;
+
+    pub use ::matching::Matching;
+    pub use ::matching::GroupMatching;
+    pub use ::matching::BandPath;
+    pub use ::matching::PathLink;
+
+    pub use ::phase::PhaseConvention;
 }
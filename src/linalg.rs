@@ -0,0 +1,183 @@
+//! Small dense linear algebra routines on row-major complex matrices.
+//!
+//! These are meant for matrices the size of a basis's rank, not its width.
+
+use ::lossless::Rect;
+
+/// Diagonalize a Hermitian `n x n` matrix using cyclic Jacobi rotations.
+///
+/// Returns `(w, v)` where `w` holds the eigenvalues in ascending order and
+/// column `k` of the row-major `n x n` matrix `v` is the eigenvector for `w[k]`.
+/// Only the upper triangle of `a` is read.
+pub(crate) fn eigh(a: &[Rect], n: usize) -> (Vec<f64>, Vec<Rect>) {
+    assert_eq!(a.len(), n * n);
+
+    let mut a: Vec<Rect> = (0..n * n).map(|ij| {
+        let (i, j) = (ij / n, ij % n);
+        if i < j {
+            a[ij]
+        } else if i > j {
+            a[j * n + i].conj()
+        } else {
+            a[ij].real.into()
+        }
+    }).collect();
+    let mut v = vec![Rect::zero(); n * n];
+    for i in 0..n {
+        v[i * n + i] = Rect::one();
+    }
+
+    let total: f64 = a.iter().map(|c| c.sqnorm()).sum();
+    for _sweep in 0..MAX_SWEEPS {
+        let off: f64 = (0..n).flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i * n + j].sqnorm())
+            .sum();
+        if off <= f64::EPSILON * f64::EPSILON * total {
+            break;
+        }
+
+        for p in 0..n {
+            for q in p + 1..n {
+                let b = a[p * n + q];
+                if b.abs() == 0.0 {
+                    continue;
+                }
+                let (alpha, gamma) = (a[p * n + p].real, a[q * n + q].real);
+
+                // The phase e^{-i arg b} on row/column q makes the 2x2 block real,
+                // which is then diagonalized by an ordinary Jacobi rotation.
+                let phase = (b / b.abs()).conj();
+                let tau = (gamma - alpha) / (2.0 * b.abs());
+                let t = tau.signum() / (tau.abs() + f64::sqrt(1.0 + tau * tau));
+                let c = 1.0 / f64::sqrt(1.0 + t * t);
+                let s = t * c;
+
+                // rows p and q of U, restricted to columns p and q
+                let u = [[Rect::from(c), Rect::from(s)], [phase * -s, phase * c]];
+                rotate_columns(&mut a, n, p, q, &u);
+                rotate_columns(&mut v, n, p, q, &u);
+                for k in 0..n {
+                    let (ap, aq) = (a[p * n + k], a[q * n + k]);
+                    a[p * n + k] = u[0][0].conj() * ap + u[1][0].conj() * aq;
+                    a[q * n + k] = u[0][1].conj() * ap + u[1][1].conj() * aq;
+                }
+                a[p * n + q] = Rect::zero();
+                a[q * n + p] = Rect::zero();
+                a[p * n + p].imag = 0.0;
+                a[q * n + q].imag = 0.0;
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| a[i * n + i].real.partial_cmp(&a[j * n + j].real).unwrap_or(::std::cmp::Ordering::Equal));
+    let w = order.iter().map(|&k| a[k * n + k].real).collect();
+    let v = (0..n * n).map(|ij| v[(ij / n) * n + order[ij % n]]).collect();
+    (w, v)
+}
+
+// (Jacobi converges quadratically; this is only reached for pathological input)
+const MAX_SWEEPS: usize = 100;
+
+/// `m <- m U` for a unitary `U` that only mixes columns `p` and `q`.
+fn rotate_columns(m: &mut [Rect], n: usize, p: usize, q: usize, u: &[[Rect; 2]; 2]) {
+    for k in 0..n {
+        let (mp, mq) = (m[k * n + p], m[k * n + q]);
+        m[k * n + p] = mp * u[0][0] + mq * u[1][0];
+        m[k * n + q] = mp * u[0][1] + mq * u[1][1];
+    }
+}
+
+/// Compute the singular values of the matrix whose columns are `cols`, in descending order,
+/// using one-sided Jacobi rotations.
+///
+/// Unlike the square roots of the eigenvalues of `A^H A`, these have good relative
+/// accuracy even when they are tiny.  There is one value for each column.
+pub(crate) fn singular_values(mut cols: Vec<Vec<Rect>>) -> Vec<f64> {
+    let n = cols.len();
+    for _sweep in 0..MAX_SWEEPS {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in p + 1..n {
+                let alpha: f64 = cols[p].iter().map(|c| c.sqnorm()).sum();
+                let beta: f64 = cols[q].iter().map(|c| c.sqnorm()).sum();
+                let gamma: Rect = cols[p].iter().zip(&cols[q]).map(|(a, &b)| a.conj() * b).sum();
+                if gamma.abs() <= f64::EPSILON * f64::sqrt(alpha * beta) {
+                    continue;
+                }
+                rotated = true;
+
+                // As in eigh, the phase on column q makes <p|q> real, and the
+                // rotation then makes the columns orthogonal.
+                let phase = (gamma / gamma.abs()).conj();
+                let zeta = (beta - alpha) / (2.0 * gamma.abs());
+                let t = zeta.signum() / (zeta.abs() + f64::sqrt(1.0 + zeta * zeta));
+                let c = 1.0 / f64::sqrt(1.0 + t * t);
+                let s = t * c;
+
+                let (left, right) = cols.split_at_mut(q);
+                for (x, y) in left[p].iter_mut().zip(&mut right[0]) {
+                    let (xp, yp) = (*x, *y * phase);
+                    *x = xp * c - yp * s;
+                    *y = xp * s + yp * c;
+                }
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+
+    let mut out: Vec<f64> = cols.iter().map(|col| col.iter().map(|c| c.sqnorm()).sum::<f64>().sqrt()).collect();
+    out.sort_by(|a, b| b.partial_cmp(a).unwrap_or(::std::cmp::Ordering::Equal));
+    out
+}
+
+#[test]
+fn test_eigh() {
+    let n = 7;
+    let mut a = vec![Rect::zero(); n * n];
+    for i in 0..n {
+        for j in i..n {
+            let x = Rect { real: 0.5 - ::rand::random::<f64>(), imag: 0.5 - ::rand::random::<f64>() };
+            a[i * n + j] = if i == j { x.real.into() } else { x };
+            a[j * n + i] = a[i * n + j].conj();
+        }
+    }
+
+    let (w, v) = eigh(&a, n);
+    assert!(w.windows(2).all(|w| w[0] <= w[1]));
+    for k in 0..n {
+        for i in 0..n {
+            // (A v)_i = w v_i
+            let av: Rect = (0..n).map(|j| a[i * n + j] * v[j * n + k]).sum();
+            assert!((av - v[i * n + k] * w[k]).abs() < 1e-12, "({},{})", i, k);
+        }
+        for l in 0..n {
+            let dot: Rect = (0..n).map(|i| v[i * n + k].conj() * v[i * n + l]).sum();
+            let expected = if k == l { 1.0 } else { 0.0 };
+            assert!((dot - Rect::from(expected)).abs() < 1e-12, "({},{})", k, l);
+        }
+    }
+}
+
+#[test]
+fn test_singular_values() {
+    let x = |real, imag| Rect { real, imag };
+    // orthogonal columns with norms 5, 1 and 1e-20, with the first two mixed
+    // by a unitary matrix (which leaves the singular values unchanged)
+    let (col0, col1) = ([x(3.0, 0.0), x(0.0, 4.0), x(0.0, 0.0)], [x(0.0, 0.8), x(0.6, 0.0), x(0.0, 0.0)]);
+    let i = Rect::i();
+    let cols = vec![
+        (0..3).map(|k| col0[k] * 0.6 + col1[k] * i * 0.8).collect::<Vec<_>>(),
+        (0..3).map(|k| col0[k] * i * 0.8 + col1[k] * 0.6).collect(),
+        vec![x(0.0, 0.0), x(0.0, 0.0), x(0.0, 1e-20)],
+    ];
+    // also rotate the components, so that the small column overlaps the others
+    let cols = cols.into_iter().map(|c| vec![c[0] * 0.6 + c[2] * 0.8, c[1], c[2] * 0.6 - c[0] * 0.8]).collect();
+
+    let values = singular_values(cols);
+    assert!((values[0] - 5.0).abs() < 1e-14);
+    assert!((values[1] - 1.0).abs() < 1e-14);
+    assert!((values[2] - 1e-20).abs() < 1e-34);
+}
@@ -0,0 +1,487 @@
+//! Band uncrossing: matching up the kets of two bases.
+
+use ::errors::{Result, Error, check_rank, unwrap};
+use ::{lossless, compact};
+
+/// A one-to-one assignment between the kets of two bases.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matching {
+    /// `perm[i]` is the index of the ket in the second basis matched to ket `i` of the first.
+    pub perm: Vec<usize>,
+    /// `overlaps[i]` is the overlap between ket `i` of the first basis and its match.
+    pub overlaps: Vec<f64>,
+}
+
+impl Matching {
+    /// Find the permutation that maximizes the total overlap, given a row-major
+    /// `n x n` matrix of overlaps.
+    pub(crate) fn maximize(overlaps: &[f64], n: usize) -> Matching {
+        assert_eq!(overlaps.len(), n * n);
+
+        // (a NaN cost would prevent the algorithm from terminating)
+        let costs: Vec<f64> = overlaps.iter().map(|&x| if x.is_finite() { -x } else { 0.0 }).collect();
+        let perm = hungarian(&costs, n);
+        let overlaps = perm.iter().enumerate().map(|(i, &j)| overlaps[i * n + j]).collect();
+        Matching { perm, overlaps }
+    }
+
+    /// Sum of the overlaps of all matched pairs.
+    pub fn total_overlap(&self) -> f64 { self.overlaps.iter().sum() }
+
+    /// The smallest overlap of any matched pair.
+    ///
+    /// This is infinite for an empty matching.
+    pub fn min_overlap(&self) -> f64 {
+        self.overlaps.iter().cloned().fold(f64::INFINITY, f64::min)
+    }
+
+    /// The permutation in the other direction, mapping kets of the
+    /// second basis to kets of the first.
+    pub fn inverse_perm(&self) -> Vec<usize> {
+        let mut inv = vec![0; self.perm.len()];
+        for (i, &j) in self.perm.iter().enumerate() {
+            inv[j] = i;
+        }
+        inv
+    }
+}
+
+/// A correspondence between groups of kets (e.g. degenerate subspaces) in two bases.
+///
+/// Every ket of either basis belongs to exactly one group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupMatching {
+    /// For each group, the indices of its kets in the first and second basis.
+    pub groups: Vec<(Vec<usize>, Vec<usize>)>,
+    /// For each group, the projection weight `sum_{i,j} |<a_i|b_j>|^2` between the
+    /// two subspaces, which equals their dimension when they span the same space.
+    pub weights: Vec<f64>,
+}
+
+impl GroupMatching {
+    /// Pair up two given groupings to maximize the total projection weight.
+    ///
+    /// `labels_a[i]` is the group of ket `i` in the first basis, and likewise for `labels_b`.
+    pub(crate) fn from_labels(
+        overlaps: &[f64],
+        labels_a: &[usize],
+        labels_b: &[usize],
+    ) -> Result<GroupMatching> {
+        let members_a = group_members(labels_a)?;
+        let members_b = group_members(labels_b)?;
+        if members_a.len() != members_b.len() {
+            return Err(Error::GroupCountMismatch { first: members_a.len(), second: members_b.len() });
+        }
+
+        let n = members_a.len();
+        let mut group_weights = vec![0.0; n * n];
+        for (g, a) in members_a.iter().enumerate() {
+            for (h, b) in members_b.iter().enumerate() {
+                group_weights[g * n + h] = projection_weight(overlaps, labels_b.len(), a, b);
+            }
+        }
+
+        let Matching { perm, overlaps: weights } = Matching::maximize(&group_weights, n);
+        let mut members_b: Vec<_> = members_b.into_iter().map(Some).collect();
+        let groups = members_a.into_iter().zip(perm)
+            .map(|(a, h)| (a, members_b[h].take().expect("(BUG!) not a permutation")))
+            .collect();
+        Ok(GroupMatching { groups, weights })
+    }
+
+    /// Form groups from the connected components of the graph whose edges are
+    /// the pairs of kets with overlap greater than `threshold`.
+    pub(crate) fn detect(overlaps: &[f64], rank_a: usize, rank_b: usize, threshold: f64) -> GroupMatching {
+        assert_eq!(overlaps.len(), rank_a * rank_b);
+
+        // union-find over the kets of both bases; those of `b` come after those of `a`.
+        let mut parent: Vec<usize> = (0..rank_a + rank_b).collect();
+        fn root(parent: &mut [usize], mut x: usize) -> usize {
+            while parent[x] != x {
+                parent[x] = parent[parent[x]];
+                x = parent[x];
+            }
+            x
+        }
+        for i in 0..rank_a {
+            for j in 0..rank_b {
+                if overlaps[i * rank_b + j] > threshold {
+                    let (ri, rj) = (root(&mut parent, i), root(&mut parent, rank_a + j));
+                    parent[usize::max(ri, rj)] = usize::min(ri, rj);
+                }
+            }
+        }
+
+        // number the groups in order of first appearance
+        let mut group_of_root = vec![None; rank_a + rank_b];
+        let mut groups: Vec<(Vec<usize>, Vec<usize>)> = vec![];
+        for node in 0..rank_a + rank_b {
+            let r = root(&mut parent, node);
+            let g = *group_of_root[r].get_or_insert_with(|| {
+                groups.push((vec![], vec![]));
+                groups.len() - 1
+            });
+            if node < rank_a {
+                groups[g].0.push(node);
+            } else {
+                groups[g].1.push(node - rank_a);
+            }
+        }
+
+        let weights = groups.iter().map(|(a, b)| projection_weight(overlaps, rank_b, a, b)).collect();
+        GroupMatching { groups, weights }
+    }
+}
+
+/// The links between consecutive bases along a path, e.g. of q-points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandPath {
+    /// `links[s]` connects the kets of basis `s` to those of basis `s + 1`.
+    pub links: Vec<PathLink>,
+}
+
+/// The matching between two consecutive bases of a [`BandPath`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathLink {
+    pub matching: Matching,
+    /// For each ket, the overlap with its match minus the largest overlap
+    /// with any other ket in the same row or column of the overlap matrix.
+    ///
+    /// A value near 1 is a clean link; values near or below 0 indicate a crossing
+    /// that could plausibly have been resolved either way.
+    pub confidence: Vec<f64>,
+    /// For each ket, whether the overlap with its match fell below the threshold.
+    pub ambiguous: Vec<bool>,
+}
+
+impl PathLink {
+    pub(crate) fn from_overlaps(overlaps: &[f64], n: usize, threshold: f64) -> PathLink {
+        let matching = Matching::maximize(overlaps, n);
+        let confidence = matching.perm.iter().enumerate().map(|(i, &j)| {
+            let row = (0..n).filter(|&k| k != j).map(|k| overlaps[i * n + k]);
+            let col = (0..n).filter(|&k| k != i).map(|k| overlaps[k * n + j]);
+            let competitor = row.chain(col).fold(0.0, f64::max);
+            overlaps[i * n + j] - competitor
+        }).collect();
+        let ambiguous = matching.overlaps.iter().map(|&x| x < threshold || x.is_nan()).collect();
+        PathLink { matching, confidence, ambiguous }
+    }
+}
+
+impl BandPath {
+    /// Follow each ket of the first basis along the path.
+    ///
+    /// `out[s][i]` is the index in basis `s` of the band that starts as ket `i`
+    /// of the first basis.  (there is one more row than there are links)
+    pub fn band_indices(&self) -> Vec<Vec<usize>> {
+        let n = self.links.first().map_or(0, |link| link.matching.perm.len());
+        let mut out = vec![(0..n).collect::<Vec<_>>()];
+        for link in &self.links {
+            let next = out.last().unwrap().iter().map(|&i| link.matching.perm[i]).collect();
+            out.push(next);
+        }
+        out
+    }
+
+    /// Every `(step, ket)` whose link to basis `step + 1` was flagged as ambiguous.
+    pub fn ambiguous_links(&self) -> Vec<(usize, usize)> {
+        self.links.iter().enumerate().flat_map(|(s, link)| {
+            link.ambiguous.iter().enumerate().filter(|&(_, &a)| a).map(move |(i, _)| (s, i))
+        }).collect()
+    }
+}
+
+// Collect the kets of each group.  Unused labels are skipped, so that the
+// groups are numbered densely in order of their labels.
+fn group_members(labels: &[usize]) -> Result<Vec<Vec<usize>>> {
+    let count = labels.len();
+    let mut members = vec![vec![]; count];
+    for (i, &g) in labels.iter().enumerate() {
+        if g >= count {
+            return Err(Error::GroupLabel { label: g, count });
+        }
+        members[g].push(i);
+    }
+    members.retain(|m| !m.is_empty());
+    Ok(members)
+}
+
+fn projection_weight(overlaps: &[f64], cols: usize, rows_of: &[usize], cols_of: &[usize]) -> f64 {
+    rows_of.iter().map(|&i| cols_of.iter().map(|&j| overlaps[i * cols + j]).sum::<f64>()).sum()
+}
+
+// (compact overlaps are `f32`, but the algorithms all work in `f64`)
+fn widen<T: Into<f64>>(overlaps: Vec<T>) -> Vec<f64> {
+    overlaps.into_iter().map(Into::into).collect()
+}
+
+// The API is identical for both representations, as all of the work is
+// done on the overlap matrix.
+macro_rules! impl_basis_matching {
+    ($kind:ident) => {
+        impl $kind::Basis {
+            /// Find the one-to-one matching between the kets of `self` and `other`
+            /// that maximizes the total overlap.
+            ///
+            /// Panics if the bases differ in width or rank.
+            pub fn match_kets(&self, other: &$kind::Basis) -> Matching {
+                unwrap(self.try_match_kets(other))
+            }
+
+            pub fn try_match_kets(&self, other: &$kind::Basis) -> Result<Matching> {
+                check_rank(self.rank(), other.rank())?;
+                let overlaps = widen(self.try_overlap_matrix(other)?);
+                Ok(Matching::maximize(&overlaps, self.rank()))
+            }
+
+            /// Pair up groups of kets (e.g. degenerate subspaces) between two bases,
+            /// maximizing the total projection weight between paired subspaces.
+            ///
+            /// `labels[i]` and `other_labels[j]` assign each ket of `self` and `other` to a group.
+            /// Labels must be less than the rank, but need not be contiguous.
+            /// Both groupings must have the same number of groups.
+            pub fn match_groups(
+                &self,
+                other: &$kind::Basis,
+                labels: &[usize],
+                other_labels: &[usize],
+            ) -> GroupMatching {
+                unwrap(self.try_match_groups(other, labels, other_labels))
+            }
+
+            pub fn try_match_groups(
+                &self,
+                other: &$kind::Basis,
+                labels: &[usize],
+                other_labels: &[usize],
+            ) -> Result<GroupMatching> {
+                check_rank(self.rank(), labels.len())?;
+                check_rank(other.rank(), other_labels.len())?;
+                let overlaps = widen(self.try_overlap_matrix(other)?);
+                GroupMatching::from_labels(&overlaps, labels, other_labels)
+            }
+
+            /// Find groups of kets in the two bases that map into each other, by connecting
+            /// any two kets whose overlap exceeds `threshold`.
+            ///
+            /// Panics if the bases differ in width.
+            pub fn detect_groups(&self, other: &$kind::Basis, threshold: f64) -> GroupMatching {
+                unwrap(self.try_detect_groups(other, threshold))
+            }
+
+            pub fn try_detect_groups(&self, other: &$kind::Basis, threshold: f64) -> Result<GroupMatching> {
+                let overlaps = widen(self.try_overlap_matrix(other)?);
+                Ok(GroupMatching::detect(&overlaps, self.rank(), other.rank(), threshold))
+            }
+
+            /// Match up the kets of each consecutive pair of bases along a path.
+            ///
+            /// Links whose overlap is less than `threshold` are flagged as ambiguous.
+            ///
+            /// Panics if the bases differ in width or rank.
+            pub fn connect_path(bases: &[$kind::Basis], threshold: f64) -> BandPath {
+                unwrap($kind::Basis::try_connect_path(bases, threshold))
+            }
+
+            pub fn try_connect_path(bases: &[$kind::Basis], threshold: f64) -> Result<BandPath> {
+                let links = bases.windows(2).map(|pair| {
+                    check_rank(pair[0].rank(), pair[1].rank())?;
+                    let overlaps = widen(pair[0].try_overlap_matrix(&pair[1])?);
+                    Ok(PathLink::from_overlaps(&overlaps, pair[0].rank(), threshold))
+                }).collect::<Result<_>>()?;
+                Ok(BandPath { links })
+            }
+        }
+    };
+}
+
+impl_basis_matching!(lossless);
+impl_basis_matching!(compact);
+
+/// Solve the assignment problem for a row-major `n x n` cost matrix,
+/// using the O(n^3) Hungarian algorithm (Kuhn-Munkres with potentials).
+///
+/// Returns `perm` minimizing `sum_i cost[i][perm[i]]`.
+fn hungarian(cost: &[f64], n: usize) -> Vec<usize> {
+    // Index 0 of these vectors is a sentinel; rows and columns are 1-based.
+    let mut u = vec![0.0; n + 1]; // row potentials
+    let mut v = vec![0.0; n + 1]; // column potentials
+    let mut row_of = vec![0; n + 1]; // row assigned to each column
+    let mut way = vec![0; n + 1]; // previous column on the augmenting path
+
+    for row in 1..n + 1 {
+        row_of[0] = row;
+        let mut col0 = 0;
+        let mut min_slack = vec![f64::INFINITY; n + 1];
+        let mut used = vec![false; n + 1];
+        loop {
+            used[col0] = true;
+            let row0 = row_of[col0];
+            let mut delta = f64::INFINITY;
+            let mut col1 = 0;
+            for col in 1..n + 1 {
+                if used[col] {
+                    continue;
+                }
+                let slack = cost[(row0 - 1) * n + (col - 1)] - u[row0] - v[col];
+                if slack < min_slack[col] {
+                    min_slack[col] = slack;
+                    way[col] = col0;
+                }
+                if min_slack[col] < delta {
+                    delta = min_slack[col];
+                    col1 = col;
+                }
+            }
+            for col in 0..n + 1 {
+                if used[col] {
+                    u[row_of[col]] += delta;
+                    v[col] -= delta;
+                } else {
+                    min_slack[col] -= delta;
+                }
+            }
+            col0 = col1;
+            if row_of[col0] == 0 {
+                break;
+            }
+        }
+        // flip the augmenting path
+        loop {
+            let col1 = way[col0];
+            row_of[col0] = row_of[col1];
+            col0 = col1;
+            if col0 == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut perm = vec![0; n];
+    for col in 1..n + 1 {
+        perm[row_of[col] - 1] = col - 1;
+    }
+    perm
+}
+
+#[test]
+fn test_hungarian() {
+    // brute force over all permutations of a small random matrix
+    fn permutations(n: usize) -> Vec<Vec<usize>> {
+        match n {
+            0 => vec![vec![]],
+            _ => {
+                permutations(n - 1).into_iter().flat_map(|p| {
+                    (0..n).map(move |k| {
+                        let mut p = p.clone();
+                        p.insert(k, n - 1);
+                        p
+                    })
+                }).collect()
+            },
+        }
+    }
+
+    for _ in 0..20 {
+        let n = 5;
+        let cost: Vec<f64> = (0..n * n).map(|_| ::rand::random::<f64>()).collect();
+        let total = |p: &[usize]| p.iter().enumerate().map(|(i, &j)| cost[i * n + j]).sum::<f64>();
+
+        let best = permutations(n).into_iter()
+            .map(|p| total(&p))
+            .fold(f64::INFINITY, f64::min);
+        assert!((total(&hungarian(&cost, n)) - best).abs() < 1e-12);
+    }
+    assert_eq!(hungarian(&[], 0), Vec::<usize>::new());
+}
+
+#[test]
+fn test_match_kets() {
+    let a = lossless::Basis::new(vec![
+        1.0, 0.0, 0.0,  0.0, 0.0, 0.0,
+        0.0, 0.6, 0.8,  0.0, 0.0, 0.0,
+        0.0, 0.8, -0.6, 0.0, 0.0, 0.0,
+    ], 3);
+    // a permuted copy, with a phase on one ket
+    let b = lossless::Basis::new(vec![
+        0.0, 0.8, -0.6, 0.0, 0.0, 0.0,
+        0.0, 0.0, 0.0,  1.0, 0.0, 0.0,
+        0.0, 0.6, 0.8,  0.0, 0.0, 0.0,
+    ], 3);
+
+    let matching = a.match_kets(&b);
+    assert_eq!(matching.perm, vec![1, 2, 0]);
+    assert_eq!(matching.inverse_perm(), vec![2, 0, 1]);
+    assert!((matching.total_overlap() - 3.0).abs() < 1e-12);
+
+    let matching = a.lossy_compress().match_kets(&b.lossy_compress());
+    assert_eq!(matching.perm, vec![1, 2, 0]);
+    assert!(matching.min_overlap() > 0.99);
+}
+
+#[test]
+fn test_match_groups() {
+    let s = 0.5f64.sqrt();
+    // kets 0 and 1 span a degenerate subspace, and ket 2 is alone
+    let a = lossless::Basis::new(vec![
+        1.0, 0.0, 0.0,  0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0,  0.0, 0.0, 0.0,
+        0.0, 0.0, 1.0,  0.0, 0.0, 0.0,
+    ], 3);
+    // the lone ket moves to the front, and the degenerate pair is rotated
+    let b = lossless::Basis::new(vec![
+        0.0, 0.0, 1.0,  0.0, 0.0, 0.0,
+        s,   s,   0.0,  0.0, 0.0, 0.0,
+        s,   -s,  0.0,  0.0, 0.0, 0.0,
+    ], 3);
+
+    let matching = a.match_groups(&b, &[0, 0, 1], &[0, 1, 1]);
+    assert_eq!(matching.groups, vec![(vec![0, 1], vec![1, 2]), (vec![2], vec![0])]);
+    assert!((matching.weights[0] - 2.0).abs() < 1e-12);
+    assert!((matching.weights[1] - 1.0).abs() < 1e-12);
+
+    let detected = a.detect_groups(&b, 0.1);
+    assert_eq!(detected.groups, matching.groups);
+    assert_eq!(detected.weights, matching.weights);
+    assert_eq!(
+        a.try_detect_groups(&lossless::Basis::new(vec![], 2), 0.1),
+        Err(Error::WidthMismatch { expected: 3, actual: 2 }),
+    );
+
+    assert_eq!(
+        a.try_match_groups(&b, &[0, 1, 2], &[0, 0, 1]),
+        Err(Error::GroupCountMismatch { first: 3, second: 2 }),
+    );
+    assert_eq!(
+        a.try_match_groups(&b, &[0, 0, usize::MAX], &[0, 1, 1]),
+        Err(Error::GroupLabel { label: usize::MAX, count: 3 }),
+    );
+    // labels need not be contiguous
+    let matching = a.match_groups(&b, &[2, 2, 0], &[0, 2, 2]);
+    assert_eq!(matching.groups, vec![(vec![2], vec![0]), (vec![0, 1], vec![1, 2])]);
+}
+
+#[test]
+fn test_connect_path() {
+    let s = 0.5f64.sqrt();
+    let bases: Vec<_> = vec![
+        vec![1.0, 0.0, 0.0, 1.0],
+        // swap
+        vec![0.0, 1.0, 1.0, 0.0],
+        // halfway between the two kets: impossible to call
+        vec![s, s, s, -s],
+    ].into_iter().map(|data| {
+        let real = data.chunks(2).flat_map(|k| k.iter().cloned().chain(vec![0.0, 0.0])).collect();
+        lossless::Basis::new(real, 2).lossy_compress()
+    }).collect();
+
+    let path = compact::Basis::connect_path(&bases, 0.75);
+    assert_eq!(path.links.len(), 2);
+    assert_eq!(path.links[0].matching.perm, vec![1, 0]);
+    assert!(path.links[0].confidence.iter().all(|&c| c > 0.99));
+    assert_eq!(path.band_indices()[..2], [vec![0, 1], vec![1, 0]]);
+    assert!(path.links[1].confidence.iter().all(|&c| c.abs() < 1e-3));
+    assert_eq!(path.ambiguous_links(), vec![(1, 0), (1, 1)]);
+
+    assert_eq!(compact::Basis::connect_path(&bases[..1], 0.75).links, vec![]);
+}
@@ -0,0 +1,355 @@
+//! Orthonormalization methods other than the plain MGS of `Basis::orthonormalize`.
+
+use ::lossless::{Basis, Rect};
+use ::errors::{Result, Error, unwrap};
+use ::linalg::eigh;
+
+impl Basis {
+    /// Orthonormalize a basis using Householder reflections.
+    ///
+    /// Returns `(q, r)` such that ket `j` of `self` equals `sum_i r[i][j] q_i`,
+    /// where `r` is a row-major `q.rank() x self.rank()` upper triangular matrix
+    /// with a real, nonnegative diagonal.  (`q.rank()` is the lesser of the rank
+    /// and width of `self`)
+    ///
+    /// Unlike `orthonormalize`, this remains stable for ill-conditioned input.
+    /// `q` is orthonormal even if the kets are linearly dependent, in which case
+    /// the corresponding diagonal elements of `r` will be (nearly) zero.
+    pub fn orthonormalize_qr(&self) -> (Basis, Vec<Rect>) {
+        let (width, rank) = (self.width(), self.rank());
+        let count = usize::min(width, rank);
+
+        let mut cols = to_columns(self);
+        let reflectors = triangularize(&mut cols, width);
+
+        // Choose phases that put a positive real number on the diagonal.
+        let phases: Vec<Rect> = (0..count).map(|i| {
+            let d = cols[i][i];
+            if d.abs() == 0.0 { Rect::one() } else { d / d.abs() }
+        }).collect();
+
+        let mut r = vec![Rect::zero(); count * rank];
+        for i in 0..count {
+            r[i * rank + i] = cols[i][i].abs().into();
+            for j in i + 1..rank {
+                r[i * rank + j] = phases[i].conj() * cols[j][i];
+            }
+        }
+
+        let q_cols: Vec<Vec<Rect>> = (0..count).map(|i| apply_q(&reflectors, width, i, phases[i])).collect();
+        (from_columns(&q_cols, width), r)
+    }
+
+    /// Construct an orthonormal basis for the orthogonal complement of the span of `self`,
+    /// so that the kets of `self` and of the output together span the full space.
+    ///
+    /// The kets of `self` must be linearly independent.  (they need not be orthonormal)
+    pub fn complement(&self) -> Basis {
+        let width = self.width();
+        let mut cols = to_columns(self);
+        let reflectors = triangularize(&mut cols, width);
+
+        // The trailing columns of Q are orthogonal to everything that Q R spans.
+        let q_cols: Vec<Vec<Rect>> = (reflectors.len()..width)
+            .map(|i| apply_q(&reflectors, width, i, Rect::one()))
+            .collect();
+        from_columns(&q_cols, width)
+    }
+
+    /// Orthonormalize a basis using Löwdin's symmetric method.
+    ///
+    /// Ket `j` becomes `sum_i (S^{-1/2})_{ij} |i>`, where `S` is the matrix of `<i|j>`.
+    /// Unlike Gram Schmidt, this treats all kets equally, and gives the orthonormal
+    /// basis closest to `self` in the least squares sense.
+    ///
+    /// Panics if the kets are linearly dependent; see `try_lowdin_orthonormalize`.
+    pub fn lowdin_orthonormalize(&self) -> Basis {
+        unwrap(self.try_lowdin_orthonormalize())
+    }
+
+    /// Orthonormalize a basis using Löwdin's symmetric method, failing if the kets are
+    /// linearly dependent.
+    ///
+    /// Kets are considered dependent when an eigenvalue of `S` is not greater than
+    /// `LOWDIN_TOL` times the largest, as the result would then be dominated by rounding error.
+    pub fn try_lowdin_orthonormalize(&self) -> Result<Basis> {
+        let n = self.rank();
+        let (w, v) = eigh(&self.dot_matrix(self), n);
+
+        // (written so that NaN is also rejected)
+        let largest = w.last().cloned().unwrap_or(0.0);
+        if !w.iter().all(|&x| x > largest * LOWDIN_TOL) {
+            return Err(Error::LinearlyDependent { smallest: w[0], largest });
+        }
+
+        // S^{-1/2} = V w^{-1/2} V^H
+        let mut x = vec![Rect::zero(); n * n];
+        for i in 0..n {
+            for j in 0..n {
+                x[i * n + j] = (0..n).map(|k| v[i * n + k] * v[j * n + k].conj() / w[k].sqrt()).sum();
+            }
+        }
+
+        Ok(self.transform(&x, n))
+    }
+
+    /// Orthonormalize a basis using Modified Gram Schmidt, then repeat the process
+    /// on the result until its kets are orthogonal to within `tol`.
+    ///
+    /// A second pass is usually enough to bring the error down to machine precision.
+    /// At most `MAX_REORTHO_PASSES` passes are performed in total.
+    ///
+    /// If the kets are linearly dependent, the result and the diagnostics will contain NaN.
+    pub fn reorthonormalize(&self, tol: f64) -> (Basis, Reorthonormalization) {
+        let mut out = self.orthonormalize();
+        let max_dot_before = out.max_off_diagonal_dot();
+        let mut max_dot_after = max_dot_before;
+        let mut passes = 1;
+        while max_dot_after > tol && passes < MAX_REORTHO_PASSES {
+            out = out.orthonormalize();
+            max_dot_after = out.max_off_diagonal_dot();
+            passes += 1;
+        }
+        (out, Reorthonormalization { passes, max_dot_before, max_dot_after })
+    }
+
+    /// The largest value of `|<i|j>|` between two distinct kets.
+    ///
+    /// This is zero for a basis with fewer than two kets, and NaN if any of the
+    /// dot products are NaN.
+    pub fn max_off_diagonal_dot(&self) -> f64 {
+        let n = self.rank();
+        // (f64::max would ignore NaN)
+        self.dot_matrix(self).into_iter().enumerate()
+            .filter(|&(ij, _)| ij / n != ij % n)
+            .map(|(_, c)| c.abs())
+            .fold(0.0, |max, x| if max >= x || max.is_nan() { max } else { x })
+    }
+}
+
+/// Diagnostics from `Basis::reorthonormalize`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reorthonormalization {
+    /// Number of Gram Schmidt passes that were performed, including the first.
+    pub passes: usize,
+    /// The largest `|<i|j>|` between distinct kets after the first pass.
+    pub max_dot_before: f64,
+    /// The largest `|<i|j>|` between distinct kets in the final result.
+    pub max_dot_after: f64,
+}
+
+const MAX_REORTHO_PASSES: usize = 4;
+
+const LOWDIN_TOL: f64 = 1e-12;
+
+/// Read the kets of a basis into vectors of complex numbers.
+pub(crate) fn to_columns(basis: &Basis) -> Vec<Vec<Rect>> {
+    basis.iter().map(|ket| ket.iter().collect()).collect()
+}
+
+/// Build a basis from vectors of complex numbers of the given width.
+pub(crate) fn from_columns(cols: &[Vec<Rect>], width: usize) -> Basis {
+    let mut data = Vec::with_capacity(2 * width * cols.len());
+    for col in cols {
+        data.extend(col.iter().map(|c| c.real));
+        data.extend(col.iter().map(|c| c.imag));
+    }
+    Basis::new(data, width)
+}
+
+/// Reduce kets of the given width to upper triangular form in place, one component
+/// at a time, returning the Householder vectors `v_k` used at each step.
+fn triangularize(cols: &mut [Vec<Rect>], width: usize) -> Vec<Vec<Rect>> {
+    let count = usize::min(width, cols.len());
+    let mut reflectors = Vec::with_capacity(count);
+    for k in 0..count {
+        let v = householder_vector(&cols[k][k..]);
+        for col in &mut cols[k..] {
+            reflect(&v, &mut col[k..]);
+        }
+        reflectors.push(v);
+    }
+    reflectors
+}
+
+/// Compute column `i` of `Q = H_0 H_1 ... H_{n-1}`, times a scalar.
+fn apply_q(reflectors: &[Vec<Rect>], width: usize, i: usize, scale: Rect) -> Vec<Rect> {
+    let mut col = vec![Rect::zero(); width];
+    col[i] = scale;
+    // (H_k for k > i does not touch e_i)
+    for (k, v) in reflectors.iter().enumerate().take(i + 1).rev() {
+        reflect(v, &mut col[k..]);
+    }
+    col
+}
+
+/// Compute the unit vector `v` such that `I - 2 |v><v|` maps `x` onto a multiple of `e_0`.
+///
+/// The result is zero (i.e. the reflection is the identity) when `x` already
+/// has that form.
+fn householder_vector(x: &[Rect]) -> Vec<Rect> {
+    let tail_sqnorm: f64 = x[1..].iter().map(|c| c.sqnorm()).sum();
+    if tail_sqnorm == 0.0 {
+        return vec![Rect::zero(); x.len()];
+    }
+
+    // Reflect onto -e^{i arg x_0} |x| e_0, which avoids cancellation in v_0.
+    let norm = (x[0].sqnorm() + tail_sqnorm).sqrt();
+    let phase = if x[0].abs() == 0.0 { Rect::one() } else { x[0] / x[0].abs() };
+    let mut v = x.to_vec();
+    v[0] += phase * norm;
+
+    let v_norm = v.iter().map(|c| c.sqnorm()).sum::<f64>().sqrt();
+    v.iter_mut().for_each(|c| *c /= v_norm);
+    v
+}
+
+/// Apply `I - 2 |v><v|` to `x`.
+fn reflect(v: &[Rect], x: &mut [Rect]) {
+    let dot: Rect = v.iter().zip(x.iter()).map(|(&a, &b)| a.conj() * b).sum();
+    let dot = dot * 2.0;
+    x.iter_mut().zip(v).for_each(|(b, &a)| *b -= a * dot);
+}
+
+#[test]
+fn test_orthonormalize_qr() {
+    let check = |basis: &Basis, tol: f64| {
+        let (q, r) = basis.orthonormalize_qr();
+        let (count, rank) = (q.rank(), basis.rank());
+        for (i, a) in q.iter().enumerate() {
+            for (j, b) in q.iter().enumerate() {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((a.dot(b) - Rect::from(expected)).abs() < tol, "({},{})", i, j);
+            }
+        }
+        for i in 0..count {
+            assert!(r[i * rank + i].imag == 0.0 && r[i * rank + i].real >= 0.0);
+            for j in 0..i {
+                assert_eq!(r[i * rank + j], Rect::zero());
+            }
+        }
+        for (j, ket) in basis.iter().enumerate() {
+            for (k, c) in ket.iter().enumerate() {
+                let reconstructed: Rect = (0..count).map(|i| r[i * rank + j] * q.ket(i).iter().nth(k).unwrap()).sum();
+                assert!((reconstructed - c).abs() < tol, "({},{})", j, k);
+            }
+        }
+        (q, r)
+    };
+
+    let (dim, num_kets) = (40, 15);
+    let data = (0..dim * num_kets * 2).map(|_| 0.5 - ::rand::random::<f64>()).collect();
+    let basis = Basis::new(data, dim);
+    let (q, _) = check(&basis, 1e-12);
+    // for linearly independent input, this is the same basis as MGS produces
+    for (a, b) in q.iter().zip(&basis.orthonormalize()) {
+        assert!((a.dot(b) - Rect::one()).abs() < 1e-10);
+    }
+
+    // ket 2 is a combination of kets 0 and 1
+    let mut basis = Basis::new(vec![
+        1.0, 2.0, 0.0, 1.0,  0.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 1.0, 0.0,  0.0, 1.0, 0.0, 0.0,
+        1.0, 3.0, 1.0, 1.0,  0.0, 1.0, 0.0, 0.0,
+    ], 4);
+    basis.insert((&[0.0, 0.0, 0.0, 1.0][..], &[0.0; 4][..]));
+    let (_, r) = check(&basis, 1e-12);
+    assert!(r[2 * 4 + 2].abs() < 1e-12);
+    assert!(r[3 * 4 + 3].abs() > 0.1);
+
+    // more kets than components
+    let data = (0..3 * 5 * 2).map(|_| 0.5 - ::rand::random::<f64>()).collect();
+    let (q, r) = check(&Basis::new(data, 3), 1e-12);
+    assert_eq!((q.rank(), r.len()), (3, 3 * 5));
+}
+
+#[test]
+fn test_lowdin_orthonormalize() {
+    let (dim, num_kets) = (30, 8);
+    let data = (0..dim * num_kets * 2).map(|_| 0.5 - ::rand::random::<f64>()).collect();
+    let basis = Basis::new(data, dim);
+
+    let lowdin = basis.lowdin_orthonormalize();
+    for (i, a) in lowdin.iter().enumerate() {
+        for (j, b) in lowdin.iter().enumerate() {
+            let expected = if i == j { 1.0 } else { 0.0 };
+            assert!((a.dot(b) - Rect::from(expected)).abs() < 1e-12, "({},{})", i, j);
+        }
+    }
+
+    // symmetric in the kets: permuting the input permutes the output
+    let mut reversed = Basis::new(vec![], dim);
+    for i in (0..num_kets).rev() {
+        reversed.insert((basis.ket(i).real(), basis.ket(i).imag()));
+    }
+    let reversed = reversed.lowdin_orthonormalize();
+    for (i, a) in lowdin.iter().enumerate() {
+        assert!((a.dot(reversed.ket(num_kets - 1 - i)) - Rect::one()).abs() < 1e-12);
+    }
+
+    // an already orthonormal basis is left alone
+    let again = lowdin.lowdin_orthonormalize();
+    for (a, b) in lowdin.iter().zip(&again) {
+        assert!((a.dot(b) - Rect::one()).abs() < 1e-12);
+    }
+
+    // the second ket is a multiple of the first
+    let dependent = Basis::new(vec![1.0, 0.0, 0.0, 0.0,  2.0, 0.0, 0.0, 0.0,  0.0, 1.0, 0.0, 0.0], 2);
+    match dependent.try_lowdin_orthonormalize() {
+        Err(Error::LinearlyDependent { smallest, largest }) => {
+            assert!(smallest.abs() < 1e-12);
+            assert!((largest - 5.0).abs() < 1e-12);
+        },
+        r => panic!("{:?}", r),
+    }
+    assert_eq!(Basis::new(vec![], 2).lowdin_orthonormalize().rank(), 0);
+}
+
+#[test]
+fn test_reorthonormalize() {
+    // nearly parallel kets, which single-pass MGS handles poorly
+    let (dim, num_kets) = (100, 10);
+    let shared: Vec<f64> = (0..dim * 2).map(|_| 0.5 - ::rand::random::<f64>()).collect();
+    let data = (0..dim * num_kets * 2).map(|i| shared[i % (dim * 2)] + 1e-6 * ::rand::random::<f64>()).collect();
+    let basis = Basis::new(data, dim);
+
+    let (out, report) = basis.reorthonormalize(1e-14);
+    assert!(report.passes >= 2);
+    assert!(report.max_dot_before > 1e-14);
+    assert!(report.max_dot_after <= 1e-14);
+    assert_eq!(report.max_dot_after, out.max_off_diagonal_dot());
+    for ket in &out {
+        assert!((ket.norm() - 1.0).abs() < 1e-12);
+    }
+
+    // the second ket is a multiple of the first, so Gram Schmidt divides by zero
+    let dependent = Basis::new(vec![1.0, 0.0, 0.0, 0.0,  2.0, 0.0, 0.0, 0.0,  0.0, 1.0, 0.0, 0.0], 2);
+    let (_, report) = dependent.reorthonormalize(1e-14);
+    assert_eq!(report.passes, 1);
+    assert!(report.max_dot_before.is_nan());
+    assert!(report.max_dot_after.is_nan());
+}
+
+#[test]
+fn test_complement() {
+    // translations of two atoms in 3d
+    let s = 0.5f64.sqrt();
+    let translations = Basis::new(vec![
+        s,   0.0, 0.0, s,   0.0, 0.0,  0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+        0.0, s,   0.0, 0.0, s,   0.0,  0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+        0.0, 0.0, s,   0.0, 0.0, s,    0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+    ], 6);
+
+    let complement = translations.complement();
+    assert_eq!(complement.rank(), 3);
+    for (i, a) in complement.iter().enumerate() {
+        for (j, b) in complement.iter().enumerate() {
+            let expected = if i == j { 1.0 } else { 0.0 };
+            assert!((a.dot(b) - Rect::from(expected)).abs() < 1e-12, "({},{})", i, j);
+        }
+        assert!(translations.projection_weight(a) < 1e-24);
+    }
+
+    assert_eq!(Basis::new(vec![], 4).complement().rank(), 4);
+    assert_eq!(complement.complement().rank(), 3);
+}
@@ -0,0 +1,234 @@
+//! Fixing the arbitrary global phase of kets.
+
+use ::errors::{Result, check_width, check_rank, unwrap};
+use ::{lossless, compact};
+use ::complex::compact::PhaseTable;
+
+/// A rule for choosing the global phase of a ket.
+///
+/// Each rule picks out one component, which is made real and positive.
+/// A ket of all zeros is left alone.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PhaseConvention {
+    /// Use the component of largest magnitude (the first, in case of ties).
+    Largest,
+    /// Use the first component whose magnitude exceeds the threshold.
+    ///
+    /// A threshold of zero selects the first nonzero component.
+    FirstAbove(f64),
+}
+
+impl PhaseConvention {
+    fn choose<I: IntoIterator<Item=f64>>(self, abs: I) -> Option<usize> {
+        match self {
+            PhaseConvention::Largest => {
+                let mut best = None;
+                for (i, x) in abs.into_iter().enumerate() {
+                    if x > best.map_or(0.0, |(_, y)| y) {
+                        best = Some((i, x));
+                    }
+                }
+                best.map(|(i, _)| i)
+            },
+            PhaseConvention::FirstAbove(threshold) => {
+                abs.into_iter().position(|x| x > threshold)
+            },
+        }
+    }
+}
+
+impl<'a> lossless::KetMut<'a> {
+    /// Multiply by the unit complex number that satisfies a phase convention.
+    pub fn fix_phase(&mut self, convention: PhaseConvention) {
+        let chosen = convention.choose(self.as_ref().iter().map(lossless::Rect::abs));
+        if let Some(k) = chosen {
+            let c = self.as_ref().at(k);
+            self.scale(c.conj() / c.abs());
+            // (make it exact)
+            self.real[k] = c.abs();
+            self.imag[k] = 0.0;
+        }
+    }
+}
+
+impl<'a> compact::KetMut<'a> {
+    /// Rotate the phases to satisfy a phase convention.
+    pub fn fix_phase(&mut self, convention: PhaseConvention) {
+        if let Some(k) = convention.choose(self.abs.iter().map(|&x| x as f64)) {
+            let shift = self.phase[k];
+            for phase in self.phase.iter_mut() {
+                *phase = phase.wrapping_sub(shift);
+            }
+        }
+    }
+}
+
+impl<'a> lossless::KetRef<'a> {
+    /// Computes `min_theta || self - e^{i theta} other ||`.
+    ///
+    /// Panics if the lengths differ.
+    pub fn distance_up_to_phase<K: lossless::AsKetRef>(&self, other: K) -> f64 {
+        let other = other.as_ket_ref();
+        // (|a|^2 + |b|^2 - 2 |<a|b>| would cancel badly for nearby kets)
+        let c = unit_phase(self.dot(other).conj());
+        let sqdist: f64 = self.iter().zip(other.iter()).map(|(a, b)| (a - c * b).sqnorm()).sum();
+        sqdist.sqrt()
+    }
+}
+
+impl<'a> compact::KetRef<'a> {
+    /// Computes `min_theta || self - e^{i theta} other ||`.
+    ///
+    /// Panics if the lengths differ.
+    pub fn distance_up_to_phase<K: compact::AsKetRef>(&self, other: K) -> f32 {
+        let other = other.as_ket_ref();
+        let table = PhaseTable::get();
+        let d = self.dot(other).conj();
+        let c = if d.abs() == 0.0 { compact::Rect::one() } else { d / d.abs() };
+        let sqdist: f32 = self.iter().zip(other.iter())
+            .map(|(a, b)| (a.to_rect(table) - c * b.to_rect(table)).sqnorm())
+            .sum();
+        sqdist.sqrt()
+    }
+}
+
+// Methods that are written the same way for both representations.
+//
+// `phase` gives the factor that aligns a ket to another from their dot product.
+macro_rules! impl_phase_common {
+    ($kind:ident, real: $Real:ty, phase: $phase:expr) => {
+        impl<'a> $kind::KetRef<'a> {
+            /// Computes `e^{i theta} self` for the `theta` that minimizes its distance from `other`.
+            ///
+            /// (`theta` is the argument of `<self|other>`, rounded to the nearest phase byte
+            /// for compact kets)  Panics if the lengths differ.
+            pub fn align_phase_to<K: $kind::AsKetRef>(&self, other: K) -> $kind::Ket {
+                let mut out = self.to_owned();
+                out.as_mut().scale($phase(self.dot(other)));
+                out
+            }
+        }
+
+        impl $kind::Ket {
+            /// Change the global phase to satisfy a phase convention.
+            #[inline]
+            pub fn fix_phase(&mut self, convention: PhaseConvention) { self.as_mut().fix_phase(convention) }
+
+            #[inline]
+            pub fn align_phase_to<K: $kind::AsKetRef>(&self, other: K) -> $kind::Ket {
+                self.as_ref().align_phase_to(other)
+            }
+
+            #[inline]
+            pub fn distance_up_to_phase<K: $kind::AsKetRef>(&self, other: K) -> $Real {
+                self.as_ref().distance_up_to_phase(other)
+            }
+        }
+
+        impl $kind::Basis {
+            /// Fix the phase of each ket according to a convention.
+            pub fn fix_phases(&mut self, convention: PhaseConvention) {
+                for mut ket in self.iter_mut() {
+                    ket.fix_phase(convention);
+                }
+            }
+
+            /// Align the phase of each ket to the corresponding ket of `other`.
+            ///
+            /// Panics if the bases differ in width or rank.
+            pub fn align_phases_to(&mut self, other: &$kind::Basis) {
+                unwrap(self.try_align_phases_to(other))
+            }
+
+            pub fn try_align_phases_to(&mut self, other: &$kind::Basis) -> Result<()> {
+                check_width(self.width(), other.width())?;
+                check_rank(self.rank(), other.rank())?;
+                for (mut ket, target) in self.iter_mut().zip(other) {
+                    let c = $phase(ket.as_ref().dot(target));
+                    ket.scale(c);
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_phase_common!(lossless, real: f64, phase: unit_phase);
+impl_phase_common!(compact, real: f32, phase: phase_byte);
+
+// e^{i arg z}, or 1 if z is zero
+fn unit_phase(z: lossless::Rect) -> lossless::Rect {
+    if z.abs() == 0.0 { lossless::Rect::one() } else { z / z.abs() }
+}
+
+fn phase_byte(z: compact::Rect) -> compact::Polar {
+    compact::Polar::from_phase_byte(z.to_polar(PhaseTable::get()).phase)
+}
+
+#[test]
+fn test_fix_phase() {
+    use self::PhaseConvention::{Largest, FirstAbove};
+
+    let i = lossless::Rect::i();
+    let ket = lossless::Ket::new(vec![0.0, 1e-9, 0.0, 0.0], vec![0.0, 0.0, 2.0, -0.5]);
+    let fixed = |convention| {
+        let mut ket = ket.clone();
+        ket.fix_phase(convention);
+        ket
+    };
+
+    let out = fixed(Largest);
+    assert_eq!(out.at(2), lossless::Rect::from(2.0));
+    assert!((out.at(3) - ket.at(3) * -i).abs() < 1e-15);
+    assert_eq!(fixed(FirstAbove(0.0)).at(1), lossless::Rect::from(1e-9));
+    assert_eq!(fixed(FirstAbove(1e-6)).at(2), lossless::Rect::from(2.0));
+
+    let mut zero = lossless::Ket::new(vec![0.0; 2], vec![0.0; 2]);
+    zero.fix_phase(Largest);
+    assert_eq!(zero.real(), &[0.0, 0.0]);
+
+    let mut basis = lossless::Basis::new(vec![], 4);
+    basis.insert(&ket);
+    basis.insert(ket.as_ref() * (i * 3.0));
+    let mut compressed = basis.lossy_compress();
+
+    basis.fix_phases(Largest);
+    assert_eq!(basis.ket(1).at(2), lossless::Rect::from(6.0));
+
+    compressed.fix_phases(Largest);
+    assert_eq!(compressed.ket(0).phase(), &[192, 192, 0, 128]);
+    // (the phase of the zero component is arbitrary)
+    assert_eq!(compressed.ket(1).phase()[1..], compressed.ket(0).phase()[1..]);
+}
+
+#[test]
+fn test_align_phase() {
+    let a = lossless::Ket::new(vec![1.0, 0.5, 0.0], vec![0.0, 0.5, -1.0]);
+    let noise = lossless::Ket::new(vec![0.0, 0.01, 0.0], vec![0.02, 0.0, 0.0]);
+    let b = (&a + &noise) * lossless::Rect::from_phase(1.3);
+
+    let aligned = b.align_phase_to(&a);
+    // the optimum is at least as good as undoing the phase that was applied
+    assert!((a.as_ref() - &aligned).norm() <= noise.norm() + 1e-12);
+    assert!((b.distance_up_to_phase(&a) - (&a - &aligned).norm()).abs() < 1e-12);
+    assert!(a.distance_up_to_phase(&a) < 1e-15);
+    let nearby = &a + lossless::Ket::new(vec![1e-10, 0.0, 0.0], vec![0.0; 3]);
+    assert!((nearby.distance_up_to_phase(&a) - 1e-10).abs() < 1e-16);
+
+    let mut basis = lossless::Basis::from_kets(3, vec![&b, &a]);
+    let target = lossless::Basis::from_kets(3, vec![&a, &b]);
+    let mut compressed = basis.lossy_compress();
+
+    basis.align_phases_to(&target);
+    assert!((basis.ket(0).dot(target.ket(0)).arg()).abs() < 1e-12);
+    assert!((basis.ket(1).dot(target.ket(1)).arg()).abs() < 1e-12);
+    assert_eq!(
+        basis.try_align_phases_to(&lossless::Basis::new(vec![], 3)),
+        Err(::Error::RankMismatch { expected: 2, actual: 0 }),
+    );
+
+    compressed.align_phases_to(&target.lossy_compress());
+    let c = compressed.ket(1).align_phase_to(target.lossy_compress().ket(1));
+    assert_eq!(c.phase(), compressed.ket(1).phase());
+    assert!(compressed.ket(0).distance_up_to_phase(target.lossy_compress().ket(0)) < 0.05);
+}
@@ -0,0 +1,227 @@
+//! Projection of kets onto the span of a basis, and comparison of such spans.
+
+use ::errors::{Result, check_width, unwrap};
+use ::{lossless, compact};
+use ::complex::compact::PhaseTable;
+use ::linalg::singular_values;
+
+// The API is the same for both representations.  Sums of kets are accumulated
+// in rectangular form, so compact results are only rounded once, at the end.
+//
+// `to_rect` and `from_rect` convert between the element type of a ket and `Rect`.
+macro_rules! impl_projection {
+    ($kind:ident, real: $Real:ty, to_rect: $to_rect:expr, from_rect: $from_rect:expr) => {
+        impl $kind::Basis {
+            /// Computes `<b_i|ket>` for each ket `b_i` of the basis.
+            ///
+            /// Panics if the widths differ.
+            pub fn coefficients<K: $kind::AsKetRef>(&self, ket: K) -> Vec<$kind::Rect> {
+                unwrap(self.try_coefficients(ket))
+            }
+
+            pub fn try_coefficients<K: $kind::AsKetRef>(&self, ket: K) -> Result<Vec<$kind::Rect>> {
+                let ket = ket.as_ket_ref();
+                check_width(self.width(), ket.len())?;
+                Ok(self.iter().map(|bra| bra.dot(ket)).collect())
+            }
+
+            /// Computes `sum_i |b_i><b_i|ket>`, the projection of a ket onto the span of the basis.
+            ///
+            /// The basis is assumed to be orthonormal.  Panics if the widths differ.
+            pub fn project<K: $kind::AsKetRef>(&self, ket: K) -> $kind::Ket {
+                unwrap(self.try_project(ket))
+            }
+
+            pub fn try_project<K: $kind::AsKetRef>(&self, ket: K) -> Result<$kind::Ket> {
+                let (to_rect, from_rect) = ($to_rect, $from_rect);
+                let coeffs = self.try_coefficients(ket)?;
+                let mut sum = vec![$kind::Rect::zero(); self.width()];
+                for (b, c) in self.iter().zip(coeffs) {
+                    for (acc, x) in sum.iter_mut().zip(b) {
+                        *acc += to_rect(x) * c;
+                    }
+                }
+                Ok(sum.into_iter().map(from_rect).collect())
+            }
+
+            /// Computes `sum_i |<b_i|ket>|^2`, the probability of a ket lying within the span
+            /// of the basis.
+            ///
+            /// The basis is assumed to be orthonormal.  Panics if the widths differ.
+            pub fn projection_weight<K: $kind::AsKetRef>(&self, ket: K) -> $Real {
+                self.coefficients(ket).into_iter().map($kind::Rect::sqnorm).sum()
+            }
+
+            /// Computes `ket - sum_i |b_i><b_i|ket>`, the part of a ket orthogonal to the span
+            /// of the basis.
+            ///
+            /// The basis is assumed to be orthonormal.  Panics if the widths differ.
+            pub fn reject<K: $kind::AsKetRef>(&self, ket: K) -> $kind::Ket {
+                unwrap(self.try_reject(ket))
+            }
+
+            pub fn try_reject<K: $kind::AsKetRef>(&self, ket: K) -> Result<$kind::Ket> {
+                let (to_rect, from_rect) = ($to_rect, $from_rect);
+                let ket = ket.as_ket_ref();
+                let coeffs = self.try_coefficients(ket)?;
+                let mut sum: Vec<_> = ket.iter().map(to_rect).collect();
+                for (b, c) in self.iter().zip(coeffs) {
+                    for (acc, x) in sum.iter_mut().zip(b) {
+                        *acc -= to_rect(x) * c;
+                    }
+                }
+                Ok(sum.into_iter().map(from_rect).collect())
+            }
+
+            /// Computes the principal angles between the spans of two orthonormal bases,
+            /// in ascending order.
+            ///
+            /// There are as many angles as the smaller of the two ranks.  Their cosines
+            /// (the canonical correlations) are the singular values of the matrix of `<a_i|b_j>`.
+            ///
+            /// Panics if the widths differ.
+            pub fn principal_angles(&self, other: &$kind::Basis) -> Vec<f64> {
+                unwrap(self.try_principal_angles(other))
+            }
+
+            /// The chordal distance `sqrt(sum_k sin^2(theta_k))` between the spans of two
+            /// orthonormal bases, where `theta_k` are the principal angles.
+            ///
+            /// This is zero when one span contains the other.  Panics if the widths differ.
+            pub fn subspace_distance(&self, other: &$kind::Basis) -> f64 {
+                self.principal_angles(other).into_iter().map(|t| t.sin().powi(2)).sum::<f64>().sqrt()
+            }
+        }
+    };
+}
+
+impl_projection!(lossless, real: f64,
+    to_rect: |x: lossless::Rect| x,
+    from_rect: |x: lossless::Rect| x);
+impl_projection!(compact, real: f32,
+    to_rect: |x: compact::Polar| x.to_rect(PhaseTable::get()),
+    from_rect: |x: compact::Rect| x.to_polar(PhaseTable::get()));
+
+impl lossless::Basis {
+    pub fn try_principal_angles(&self, other: &lossless::Basis) -> Result<Vec<f64>> {
+        // With `a` the basis of lower rank, the cosines of the angles are the singular
+        // values of the matrix of <a_i|b_j>, and their sines are the singular values of
+        // the parts of the a_i orthogonal to b.  Small angles are only resolved by the
+        // sines, and angles near pi/2 only by the cosines, so each is used where it is
+        // accurate.
+        let (a, b) = if self.rank() <= other.rank() { (self, other) } else { (other, self) };
+        let k = b.rank();
+        let m = a.try_dot_matrix(b)?;
+        let cosines = singular_values(m.chunks(k.max(1)).map(|row| row.to_vec()).collect());
+        let sines = singular_values(a.iter().map(|ket| b.reject(ket).iter().collect()).collect());
+
+        // (cosines are in descending order, so both give the smallest angle first)
+        Ok(cosines.into_iter().zip(sines.into_iter().rev()).map(|(cos, sin)| {
+            if sin < ::std::f64::consts::FRAC_1_SQRT_2 { sin.asin() } else { cos.min(1.0).acos() }
+        }).collect())
+    }
+}
+
+impl compact::Basis {
+    /// This decompresses both bases; see the lossless implementation.
+    pub fn try_principal_angles(&self, other: &compact::Basis) -> Result<Vec<f64>> {
+        self.decompress().try_principal_angles(&other.decompress())
+    }
+}
+
+#[test]
+fn test_project() {
+    use ::errors::Error;
+
+    let s = 0.5f64.sqrt();
+    // spans the first two components
+    let basis = lossless::Basis::new(vec![
+        s,   s,   0.0,  0.0, 0.0, 0.0,
+        0.0, 0.0, 0.0,  s,   -s,  0.0,
+    ], 3);
+    let ket = lossless::Ket::new(vec![1.0, 2.0, 3.0], vec![0.0, 0.0, 4.0]);
+
+    let coeffs = basis.coefficients(&ket);
+    assert!((coeffs[0] - lossless::Rect::from(3.0 * s)).abs() < 1e-12);
+    assert!((coeffs[1] - lossless::Rect { real: 0.0, imag: s }).abs() < 1e-12);
+
+    let projected = basis.project(&ket);
+    let expected = [(1.0, 0.0), (2.0, 0.0), (0.0, 0.0)];
+    for (x, &(real, imag)) in projected.iter().zip(&expected) {
+        assert!((x - lossless::Rect { real, imag }).abs() < 1e-12);
+    }
+    assert!((basis.projection_weight(&ket) - 5.0).abs() < 1e-12);
+
+    let rejected = basis.reject(&ket);
+    let expected = [(0.0, 0.0), (0.0, 0.0), (3.0, 4.0)];
+    for (x, &(real, imag)) in rejected.iter().zip(&expected) {
+        assert!((x - lossless::Rect { real, imag }).abs() < 1e-12);
+    }
+
+    assert_eq!(
+        basis.try_project(lossless::Ket::new(vec![1.0], vec![0.0])).map(|_| ()),
+        Err(Error::WidthMismatch { expected: 3, actual: 1 }),
+    );
+
+    let compact_basis = basis.lossy_compress();
+    let compact_ket: compact::Ket = ket.iter()
+        .map(|x| compact::Rect { real: x.real as f32, imag: x.imag as f32 }.to_polar(PhaseTable::get()))
+        .collect();
+    assert!((compact_basis.projection_weight(&compact_ket) - 5.0).abs() < 1e-4);
+    let projected = compact_basis.project(&compact_ket);
+    for (x, &abs) in projected.iter().zip(&[1.0, 2.0, 0.0]) {
+        assert!((x.abs - abs).abs() < 1e-4);
+    }
+    let rejected = compact_basis.reject(&compact_ket);
+    for (x, &abs) in rejected.iter().zip(&[0.0, 0.0, 5.0]) {
+        assert!((x.abs - abs).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_principal_angles() {
+    use ::std::f64::consts::FRAC_PI_2;
+
+    let (c, s) = (0.3f64.cos(), 0.3f64.sin());
+    // the xy plane, and a plane containing y that is tilted by 0.3 radians
+    let a = lossless::Basis::new(vec![
+        1.0, 0.0, 0.0,  0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0,  0.0, 0.0, 0.0,
+    ], 3);
+    let b = lossless::Basis::new(vec![
+        0.0, 0.0, 0.0,  0.0, 1.0, 0.0,
+        c,   0.0, s,    0.0, 0.0, 0.0,
+    ], 3);
+    let z = lossless::Basis::new(vec![0.0, 0.0, 1.0,  0.0, 0.0, 0.0], 3);
+
+    let angles = a.principal_angles(&b);
+    assert_eq!(angles.len(), 2);
+    assert!(angles[0].abs() < 1e-15);
+    assert!((angles[1] - 0.3).abs() < 1e-12);
+    assert!((a.subspace_distance(&b) - s).abs() < 1e-12);
+
+    let angles = z.principal_angles(&a);
+    assert_eq!(angles.len(), 1);
+    assert!((angles[0] - FRAC_PI_2).abs() < 1e-12);
+    assert_eq!(a.principal_angles(&z), angles);
+
+    // a subspace of nearly full dimension, compared to itself and to a small perturbation
+    let mut c = lossless::Basis::new(vec![], 12);
+    for _ in 0..10 {
+        let real = (0..12).map(|_| ::rand::random::<f64>() - 0.5).collect();
+        let imag = (0..12).map(|_| ::rand::random::<f64>() - 0.5).collect();
+        c.insert(lossless::Ket::new(real, imag));
+    }
+    let c = c.orthonormalize_qr().0;
+    assert!(c.subspace_distance(&c) < 1e-14);
+
+    let mut d = c.clone();
+    d.ket_mut(3).axpy(lossless::Rect::from(1e-10), c.complement().ket(0));
+    let d = d.orthonormalize_qr().0;
+    let angles = c.principal_angles(&d);
+    assert!(angles[..9].iter().all(|&t| t < 1e-14));
+    assert!((angles[9] - 1e-10).abs() < 1e-14);
+
+    let angles = a.lossy_compress().principal_angles(&b.lossy_compress());
+    assert!((angles[1] - 0.3).abs() < 1e-2);
+}